@@ -0,0 +1,10 @@
+use soroban_sdk::contracterror;
+
+// Errors raised by the collection contract itself. Anything rejected by the
+// factory surfaces as the factory's own error.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotInitialized = 1,
+}
@@ -0,0 +1,50 @@
+use soroban_sdk::{contractevent, Address};
+
+// SEP-0050 events, published by the collection contract for the calls made on
+// it. Topics and data follow the standard so wallets and indexers can decode
+// them without knowing about the factory.
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent(data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Approve {
+    #[topic]
+    pub approver: Address,
+    #[topic]
+    pub token_id: u32,
+    pub approved: Address,
+    pub live_until_ledger: u32,
+}
+
+#[contractevent(data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApproveForAll {
+    #[topic]
+    pub owner: Address,
+    pub operator: Address,
+    pub live_until_ledger: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub token_id: u32,
+}
@@ -0,0 +1,104 @@
+use soroban_sdk::{contractclient, Address, BytesN, Env, Map, String, Vec};
+
+use crate::storage::MintVoucher;
+
+// Subset of the CollectionFactoryContract interface that a deployed collection
+// forwards to. Token state lives in the factory, keyed by collection_id, and
+// the factory only accepts token calls for this collection from this contract.
+#[allow(dead_code)]
+#[contractclient(name = "FactoryClient")]
+pub trait FactoryInterface {
+    fn balance_of(env: Env, collection_id: u64, address: Address) -> u32;
+    fn owner_of(env: Env, collection_id: u64, token_id: u32) -> Address;
+    fn get_approved(env: Env, collection_id: u64, token_id: u32) -> Option<Address>;
+    fn is_approved_for_all(env: Env, collection_id: u64, owner: Address, operator: Address)
+        -> bool;
+    fn token_uri(env: Env, collection_id: u64, token_id: u32) -> String;
+    fn total_supply(env: Env, collection_id: u64) -> u32;
+
+    fn mint(
+        env: Env,
+        collection_id: u64,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> u32;
+    fn minter_mint(
+        env: Env,
+        collection_id: u64,
+        minter: Address,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> u32;
+    fn mint_with_proof(
+        env: Env,
+        collection_id: u64,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+        proof: Vec<BytesN<32>>,
+        allowance: u32,
+    ) -> u32;
+    fn batch_mint(
+        env: Env,
+        collection_id: u64,
+        to: Address,
+        uris: Vec<String>,
+        attributes_list: Option<Vec<Map<String, String>>>,
+    ) -> Vec<u32>;
+    fn redeem_voucher(
+        env: Env,
+        collection_id: u64,
+        voucher: MintVoucher,
+        signature: BytesN<64>,
+        buyer: Address,
+    ) -> u32;
+
+    fn transfer(env: Env, collection_id: u64, from: Address, to: Address, token_id: u32);
+    fn batch_transfer(
+        env: Env,
+        collection_id: u64,
+        from: Address,
+        to: Address,
+        token_ids: Vec<u32>,
+    );
+    fn transfer_from(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    );
+    fn approve(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        approved: Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    );
+    fn set_approval_for_all(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        operator: Address,
+        live_until_ledger: u32,
+    );
+    fn permit(
+        env: Env,
+        collection_id: u64,
+        owner: Address,
+        spender: Address,
+        token_id: u32,
+        deadline: u32,
+        nonce: u64,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+    );
+    fn burn(env: Env, collection_id: u64, owner: Address, token_id: u32);
+
+    fn upgrade_collection(env: Env, collection_id: u64, caller: Address, new_wasm_hash: BytesN<32>);
+    fn renounce_upgradeability(env: Env, collection_id: u64, caller: Address);
+}
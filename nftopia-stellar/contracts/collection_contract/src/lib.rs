@@ -0,0 +1,378 @@
+#![no_std]
+#![allow(clippy::too_many_arguments)]
+
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Map, String, Vec};
+
+mod errors;
+mod events;
+mod factory;
+mod storage;
+
+use errors::Error;
+use factory::FactoryClient;
+use storage::{CollectionConfig, MintVoucher};
+
+// A collection deployed by the CollectionFactoryContract. It gives the
+// collection its own address while token state stays in the factory, and
+// exposes the SEP-0050 non-fungible token interface over it.
+#[contract]
+pub struct CollectionContract;
+
+#[contractimpl]
+impl CollectionContract {
+    // Called once by the factory at deployment
+    pub fn __constructor(env: Env, factory: Address, collection_id: u64, config: CollectionConfig) {
+        storage::initialize(&env, &factory, collection_id, &config);
+    }
+
+    // Collection identity
+    pub fn factory(env: Env) -> Result<Address, Error> {
+        storage::get_factory(&env)
+    }
+
+    pub fn collection_id(env: Env) -> Result<u64, Error> {
+        storage::get_collection_id(&env)
+    }
+
+    pub fn name(env: Env) -> Result<String, Error> {
+        Ok(storage::get_config(&env)?.name)
+    }
+
+    pub fn symbol(env: Env) -> Result<String, Error> {
+        Ok(storage::get_config(&env)?.symbol)
+    }
+
+    // Query functions
+    pub fn balance(env: Env, account: Address) -> Result<u32, Error> {
+        Ok(Self::factory_client(&env)?.balance_of(&storage::get_collection_id(&env)?, &account))
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Result<Address, Error> {
+        Ok(Self::factory_client(&env)?.owner_of(&storage::get_collection_id(&env)?, &token_id))
+    }
+
+    pub fn get_approved(env: Env, token_id: u32) -> Result<Option<Address>, Error> {
+        Ok(Self::factory_client(&env)?.get_approved(&storage::get_collection_id(&env)?, &token_id))
+    }
+
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> Result<bool, Error> {
+        Ok(Self::factory_client(&env)?.is_approved_for_all(
+            &storage::get_collection_id(&env)?,
+            &owner,
+            &operator,
+        ))
+    }
+
+    pub fn token_uri(env: Env, token_id: u32) -> Result<String, Error> {
+        Ok(Self::factory_client(&env)?.token_uri(&storage::get_collection_id(&env)?, &token_id))
+    }
+
+    pub fn total_supply(env: Env) -> Result<u32, Error> {
+        Ok(Self::factory_client(&env)?.total_supply(&storage::get_collection_id(&env)?))
+    }
+
+    // Mint functions. The factory applies the same mint rules as for any
+    // other collection; this contract publishes the SEP-0050 mint event.
+    pub fn mint(
+        env: Env,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> Result<u32, Error> {
+        to.require_auth();
+
+        let token_id = Self::factory_client(&env)?.mint(
+            &storage::get_collection_id(&env)?,
+            &to,
+            &uri,
+            &attributes,
+        );
+
+        events::Mint { to, token_id }.publish(&env);
+        Ok(token_id)
+    }
+
+    pub fn minter_mint(
+        env: Env,
+        minter: Address,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> Result<u32, Error> {
+        minter.require_auth();
+
+        let token_id = Self::factory_client(&env)?.minter_mint(
+            &storage::get_collection_id(&env)?,
+            &minter,
+            &to,
+            &uri,
+            &attributes,
+        );
+
+        events::Mint { to, token_id }.publish(&env);
+        Ok(token_id)
+    }
+
+    pub fn mint_with_proof(
+        env: Env,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+        proof: Vec<BytesN<32>>,
+        allowance: u32,
+    ) -> Result<u32, Error> {
+        to.require_auth();
+
+        let token_id = Self::factory_client(&env)?.mint_with_proof(
+            &storage::get_collection_id(&env)?,
+            &to,
+            &uri,
+            &attributes,
+            &proof,
+            &allowance,
+        );
+
+        events::Mint { to, token_id }.publish(&env);
+        Ok(token_id)
+    }
+
+    pub fn batch_mint(
+        env: Env,
+        to: Address,
+        uris: Vec<String>,
+        attributes_list: Option<Vec<Map<String, String>>>,
+    ) -> Result<Vec<u32>, Error> {
+        to.require_auth();
+
+        let token_ids = Self::factory_client(&env)?.batch_mint(
+            &storage::get_collection_id(&env)?,
+            &to,
+            &uris,
+            &attributes_list,
+        );
+
+        for token_id in token_ids.iter() {
+            events::Mint {
+                to: to.clone(),
+                token_id,
+            }
+            .publish(&env);
+        }
+        Ok(token_ids)
+    }
+
+    // Mints a creator-signed voucher to the buyer, who pays its price
+    pub fn redeem_voucher(
+        env: Env,
+        voucher: MintVoucher,
+        signature: BytesN<64>,
+        buyer: Address,
+    ) -> Result<u32, Error> {
+        buyer.require_auth();
+
+        let token_id = Self::factory_client(&env)?.redeem_voucher(
+            &storage::get_collection_id(&env)?,
+            &voucher,
+            &signature,
+            &buyer,
+        );
+
+        events::Mint {
+            to: buyer,
+            token_id,
+        }
+        .publish(&env);
+        Ok(token_id)
+    }
+
+    // Token functions. Each one authorizes the forwarded factory call as a
+    // sub-invocation of the call made on this contract.
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) -> Result<(), Error> {
+        from.require_auth();
+
+        Self::factory_client(&env)?.transfer(
+            &storage::get_collection_id(&env)?,
+            &from,
+            &to,
+            &token_id,
+        );
+
+        events::Transfer { from, to, token_id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<(), Error> {
+        spender.require_auth();
+
+        Self::factory_client(&env)?.transfer_from(
+            &storage::get_collection_id(&env)?,
+            &spender,
+            &from,
+            &to,
+            &token_id,
+        );
+
+        events::Transfer { from, to, token_id }.publish(&env);
+        Ok(())
+    }
+
+    pub fn batch_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_ids: Vec<u32>,
+    ) -> Result<(), Error> {
+        from.require_auth();
+
+        Self::factory_client(&env)?.batch_transfer(
+            &storage::get_collection_id(&env)?,
+            &from,
+            &to,
+            &token_ids,
+        );
+
+        for token_id in token_ids.iter() {
+            events::Transfer {
+                from: from.clone(),
+                to: to.clone(),
+                token_id,
+            }
+            .publish(&env);
+        }
+        Ok(())
+    }
+
+    // Approvals expire after live_until_ledger; the factory checks its bounds
+    pub fn approve(
+        env: Env,
+        approver: Address,
+        approved: Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        approver.require_auth();
+
+        Self::factory_client(&env)?.approve(
+            &storage::get_collection_id(&env)?,
+            &approver,
+            &approved,
+            &token_id,
+            &live_until_ledger,
+        );
+
+        events::Approve {
+            approver,
+            token_id,
+            approved,
+            live_until_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // A live_until_ledger of 0 revokes the operator
+    pub fn approve_for_all(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+
+        Self::factory_client(&env)?.set_approval_for_all(
+            &storage::get_collection_id(&env)?,
+            &owner,
+            &operator,
+            &live_until_ledger,
+        );
+
+        events::ApproveForAll {
+            owner,
+            operator,
+            live_until_ledger,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    // Approves the spender with the owner's off-chain signature instead of
+    // their auth. The deadline doubles as the approval's live_until_ledger.
+    // The signature is verified against the ed25519 key the owner registered
+    // with the factory's set_permit_key, not the owner account's signers.
+    pub fn permit(
+        env: Env,
+        owner: Address,
+        spender: Address,
+        token_id: u32,
+        deadline: u32,
+        nonce: u64,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        Self::factory_client(&env)?.permit(
+            &storage::get_collection_id(&env)?,
+            &owner,
+            &spender,
+            &token_id,
+            &deadline,
+            &nonce,
+            &public_key,
+            &signature,
+        );
+
+        events::Approve {
+            approver: owner,
+            token_id,
+            approved: spender,
+            live_until_ledger: deadline,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    pub fn burn(env: Env, from: Address, token_id: u32) -> Result<(), Error> {
+        from.require_auth();
+
+        Self::factory_client(&env)?.burn(&storage::get_collection_id(&env)?, &from, &token_id);
+
+        events::Burn { from, token_id }.publish(&env);
+        Ok(())
+    }
+
+    // Upgrades. The factory checks that the caller is the collection admin
+    // and that the collection is still upgradeable.
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        caller.require_auth();
+
+        Self::factory_client(&env)?.upgrade_collection(
+            &storage::get_collection_id(&env)?,
+            &caller,
+            &new_wasm_hash,
+        );
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    pub fn renounce_upgradeability(env: Env, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        Self::factory_client(&env)?
+            .renounce_upgradeability(&storage::get_collection_id(&env)?, &caller);
+        Ok(())
+    }
+}
+
+impl CollectionContract {
+    fn factory_client(env: &Env) -> Result<FactoryClient<'_>, Error> {
+        Ok(FactoryClient::new(env, &storage::get_factory(env)?))
+    }
+}
+
+#[cfg(test)]
+mod test;
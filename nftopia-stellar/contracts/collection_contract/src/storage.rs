@@ -0,0 +1,96 @@
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, Map, String, TryFromVal, Val};
+
+use crate::errors::Error;
+
+const DAY_IN_LEDGERS: u32 = 17280;
+const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Factory,
+    CollectionId,
+    Config,
+}
+
+// Mirrors the factory's MetadataSchema so the constructor argument decodes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MetadataSchema {
+    Basic,
+    Extended,
+    Advanced,
+}
+
+// Mirrors the factory's TransferPolicy so the constructor argument decodes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TransferPolicy {
+    Transferable,
+    Soulbound,
+    AdminTransferOnly,
+}
+
+// Mirrors the factory's CollectionConfig as passed at deployment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionConfig {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub base_uri: String,
+    pub max_supply: Option<u32>,
+    pub is_public_mint: bool,
+    pub royalty_percentage: u32,
+    pub royalty_recipient: Address,
+    pub metadata_schema: MetadataSchema,
+    pub is_pausable: bool,
+    pub is_upgradeable: bool,
+    pub transfer_policy: TransferPolicy,
+}
+
+// Mirrors the factory's MintVoucher so a signed voucher passes through as is.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintVoucher {
+    pub voucher_id: BytesN<32>,
+    pub signer: BytesN<32>,
+    pub uri: String,
+    pub attributes: Map<String, String>,
+    pub price: i128,
+    pub price_token: Option<Address>,
+    pub expires_at: u64,
+}
+
+pub fn get_factory(env: &Env) -> Result<Address, Error> {
+    read_instance(env, &DataKey::Factory)
+}
+
+pub fn get_collection_id(env: &Env) -> Result<u64, Error> {
+    read_instance(env, &DataKey::CollectionId)
+}
+
+pub fn get_config(env: &Env) -> Result<CollectionConfig, Error> {
+    read_instance(env, &DataKey::Config)
+}
+
+pub fn initialize(env: &Env, factory: &Address, collection_id: u64, config: &CollectionConfig) {
+    write_instance(env, &DataKey::Factory, factory);
+    write_instance(env, &DataKey::CollectionId, &collection_id);
+    write_instance(env, &DataKey::Config, config);
+}
+
+// The instance entry holds everything this contract stores, so its TTL is
+// extended on every access, as the factory does for its own instance.
+fn read_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Result<V, Error> {
+    let storage = env.storage().instance();
+    storage.extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    storage.get(key).ok_or(Error::NotInitialized)
+}
+
+fn write_instance<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    let storage = env.storage().instance();
+    storage.set(key, value);
+    storage.extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
@@ -0,0 +1,662 @@
+extern crate std;
+
+use std::boxed::Box;
+
+use soroban_sdk::{
+    contract, contractimpl, contracttype,
+    testutils::{storage::Instance as _, Address as _, Events as _, Ledger as _},
+    vec,
+    xdr::{
+        Limits, ReadXdr, ScSpecEntry, ScSpecEventDataFormat, ScSpecEventParamLocationV0,
+        ScSpecEventV0, ScSpecFunctionV0, ScSpecTypeDef, ScSpecTypeOption,
+    },
+    Address, BytesN, Env, IntoVal, Map, String, Symbol, Vec,
+};
+
+use crate::{
+    events,
+    storage::{CollectionConfig, MetadataSchema, MintVoucher, TransferPolicy},
+    CollectionContract, CollectionContractClient,
+};
+
+const COLLECTION_ID: u64 = 7;
+
+fn test_config(env: &Env) -> CollectionConfig {
+    CollectionConfig {
+        name: String::from_str(env, "Test Collection"),
+        symbol: String::from_str(env, "TEST"),
+        description: String::from_str(env, "A test NFT collection"),
+        base_uri: String::from_str(env, "https://api.nftopia.com/metadata/"),
+        max_supply: None,
+        is_public_mint: true,
+        royalty_percentage: 500,
+        royalty_recipient: Address::generate(env),
+        metadata_schema: MetadataSchema::Basic,
+        is_pausable: true,
+        is_upgradeable: false,
+        transfer_policy: TransferPolicy::Transferable,
+    }
+}
+
+// Stands in for the factory: keeps token owners and hands out sequential
+// IDs, so the tests see exactly what the collection contract publishes.
+#[contracttype]
+enum MockKey {
+    NextTokenId,
+    Owner(u32),
+}
+
+#[contract]
+struct MockFactory;
+
+#[contractimpl]
+impl MockFactory {
+    pub fn owner_of(env: Env, _collection_id: u64, token_id: u32) -> Address {
+        env.storage()
+            .instance()
+            .get(&MockKey::Owner(token_id))
+            .unwrap()
+    }
+
+    pub fn mint(
+        env: Env,
+        _collection_id: u64,
+        to: Address,
+        _uri: String,
+        _attributes: Option<Map<String, String>>,
+    ) -> u32 {
+        Self::mint_to(&env, &to)
+    }
+
+    pub fn minter_mint(
+        env: Env,
+        _collection_id: u64,
+        _minter: Address,
+        to: Address,
+        _uri: String,
+        _attributes: Option<Map<String, String>>,
+    ) -> u32 {
+        Self::mint_to(&env, &to)
+    }
+
+    pub fn mint_with_proof(
+        env: Env,
+        _collection_id: u64,
+        to: Address,
+        _uri: String,
+        _attributes: Option<Map<String, String>>,
+        _proof: Vec<BytesN<32>>,
+        _allowance: u32,
+    ) -> u32 {
+        Self::mint_to(&env, &to)
+    }
+
+    pub fn batch_mint(
+        env: Env,
+        _collection_id: u64,
+        to: Address,
+        uris: Vec<String>,
+        _attributes_list: Option<Vec<Map<String, String>>>,
+    ) -> Vec<u32> {
+        let mut token_ids = Vec::new(&env);
+        for _ in uris.iter() {
+            token_ids.push_back(Self::mint_to(&env, &to));
+        }
+        token_ids
+    }
+
+    pub fn redeem_voucher(
+        env: Env,
+        _collection_id: u64,
+        _voucher: MintVoucher,
+        _signature: BytesN<64>,
+        buyer: Address,
+    ) -> u32 {
+        Self::mint_to(&env, &buyer)
+    }
+
+    pub fn transfer(env: Env, _collection_id: u64, _from: Address, to: Address, token_id: u32) {
+        env.storage().instance().set(&MockKey::Owner(token_id), &to);
+    }
+
+    pub fn transfer_from(
+        env: Env,
+        _collection_id: u64,
+        _caller: Address,
+        _from: Address,
+        to: Address,
+        token_id: u32,
+    ) {
+        env.storage().instance().set(&MockKey::Owner(token_id), &to);
+    }
+
+    pub fn batch_transfer(
+        env: Env,
+        _collection_id: u64,
+        _from: Address,
+        to: Address,
+        token_ids: Vec<u32>,
+    ) {
+        for token_id in token_ids.iter() {
+            env.storage().instance().set(&MockKey::Owner(token_id), &to);
+        }
+    }
+
+    pub fn approve(
+        _env: Env,
+        _collection_id: u64,
+        _caller: Address,
+        _approved: Address,
+        _token_id: u32,
+        _live_until_ledger: u32,
+    ) {
+    }
+
+    pub fn set_approval_for_all(
+        _env: Env,
+        _collection_id: u64,
+        _caller: Address,
+        _operator: Address,
+        _live_until_ledger: u32,
+    ) {
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit(
+        _env: Env,
+        _collection_id: u64,
+        _owner: Address,
+        _spender: Address,
+        _token_id: u32,
+        _deadline: u32,
+        _nonce: u64,
+        _public_key: BytesN<32>,
+        _signature: BytesN<64>,
+    ) {
+    }
+
+    pub fn burn(env: Env, _collection_id: u64, _owner: Address, token_id: u32) {
+        env.storage().instance().remove(&MockKey::Owner(token_id));
+    }
+}
+
+impl MockFactory {
+    fn mint_to(env: &Env, to: &Address) -> u32 {
+        let storage = env.storage().instance();
+        let token_id = storage.get(&MockKey::NextTokenId).unwrap_or(1);
+        storage.set(&MockKey::NextTokenId, &(token_id + 1));
+        storage.set(&MockKey::Owner(token_id), to);
+        token_id
+    }
+}
+
+fn setup(env: &Env) -> CollectionContractClient<'_> {
+    env.mock_all_auths();
+
+    let factory = env.register(MockFactory, ());
+    let contract_id = env.register(
+        CollectionContract,
+        (factory, COLLECTION_ID, test_config(env)),
+    );
+    CollectionContractClient::new(env, &contract_id)
+}
+
+#[test]
+fn test_reads_extend_instance_ttl() {
+    let env = Env::default();
+    let factory = Address::generate(&env);
+
+    let contract_id = env.register(
+        CollectionContract,
+        (factory.clone(), COLLECTION_ID, test_config(&env)),
+    );
+    let client = CollectionContractClient::new(&env, &contract_id);
+
+    // Let the instance TTL run down past the extension threshold
+    let ttl = env.as_contract(&contract_id, || env.storage().instance().get_ttl());
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + ttl - 1);
+    assert_eq!(
+        env.as_contract(&contract_id, || env.storage().instance().get_ttl()),
+        1
+    );
+
+    assert_eq!(client.name(), String::from_str(&env, "Test Collection"));
+    assert_eq!(client.factory(), factory);
+    assert_eq!(client.collection_id(), COLLECTION_ID);
+    assert_eq!(
+        env.as_contract(&contract_id, || env.storage().instance().get_ttl()),
+        ttl
+    );
+}
+
+#[test]
+fn test_mint_publishes_sep0050_mint() {
+    let env = Env::default();
+    let collection = setup(&env);
+    let to = Address::generate(&env);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/1");
+
+    let token_id = collection.mint(&to, &uri, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), to.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+
+    // Every mint entry point publishes the same event for the recipient
+    let minter = Address::generate(&env);
+    let token_id = collection.minter_mint(&minter, &to, &uri, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), to.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+
+    let token_id = collection.mint_with_proof(&to, &uri, &None, &Vec::new(&env), &1);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), to.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.owner_of(&token_id), to);
+}
+
+#[test]
+fn test_batch_mint_publishes_one_mint_per_token() {
+    let env = Env::default();
+    let collection = setup(&env);
+    let to = Address::generate(&env);
+    let uris = vec![
+        &env,
+        String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        String::from_str(&env, "https://api.nftopia.com/metadata/2"),
+    ];
+
+    assert_eq!(collection.batch_mint(&to, &uris, &None), vec![&env, 1, 2]);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), to.clone()).into_val(&env),
+                1u32.into_val(&env),
+            ),
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), to.clone()).into_val(&env),
+                2u32.into_val(&env),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_redeem_voucher_publishes_mint_to_buyer() {
+    let env = Env::default();
+    let collection = setup(&env);
+    let buyer = Address::generate(&env);
+    let voucher = MintVoucher {
+        voucher_id: BytesN::from_array(&env, &[1; 32]),
+        signer: BytesN::from_array(&env, &[2; 32]),
+        uri: String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        attributes: Map::new(&env),
+        price: 0,
+        price_token: None,
+        expires_at: 0,
+    };
+
+    let token_id = collection.redeem_voucher(&voucher, &BytesN::from_array(&env, &[0; 64]), &buyer);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), buyer.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_transfers_publish_sep0050_transfer() {
+    let env = Env::default();
+    let collection = setup(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/1");
+    let first = collection.mint(&from, &uri, &None);
+    let second = collection.mint(&from, &uri, &None);
+
+    collection.transfer(&from, &to, &first);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), from.clone(), to.clone()).into_val(&env),
+                first.into_val(&env),
+            )
+        ]
+    );
+
+    let spender = Address::generate(&env);
+    collection.transfer_from(&spender, &to, &from, &first);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), to.clone(), from.clone()).into_val(&env),
+                first.into_val(&env),
+            )
+        ]
+    );
+
+    collection.batch_transfer(&from, &to, &vec![&env, first, second]);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), from.clone(), to.clone()).into_val(&env),
+                first.into_val(&env),
+            ),
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), from.clone(), to.clone()).into_val(&env),
+                second.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(collection.owner_of(&second), to);
+}
+
+#[test]
+fn test_approvals_publish_sep0050_approve() {
+    let env = Env::default();
+    let collection = setup(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let token_id = collection.mint(
+        &owner,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    collection.approve(&owner, &spender, &token_id, &500);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve"), owner.clone(), token_id).into_val(&env),
+                (spender.clone(), 500u32).into_val(&env),
+            )
+        ]
+    );
+
+    // A permit approves the spender until its deadline
+    collection.permit(
+        &owner,
+        &spender,
+        &token_id,
+        &600,
+        &0,
+        &BytesN::from_array(&env, &[2; 32]),
+        &BytesN::from_array(&env, &[0; 64]),
+    );
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve"), owner.clone(), token_id).into_val(&env),
+                (spender.clone(), 600u32).into_val(&env),
+            )
+        ]
+    );
+
+    let operator = Address::generate(&env);
+    collection.approve_for_all(&owner, &operator, &700);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve_for_all"), owner.clone()).into_val(&env),
+                (operator.clone(), 700u32).into_val(&env),
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_burn_publishes_sep0050_burn() {
+    let env = Env::default();
+    let collection = setup(&env);
+    let owner = Address::generate(&env);
+    let token_id = collection.mint(
+        &owner,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    collection.burn(&owner, &token_id);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "burn"), owner.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+}
+
+// SEP-0050 as the spec writes it: each function's argument types in order and
+// its return type, and each event's topics and data layout. The contract's
+// own spec entries must match, so the interface cannot drift from the
+// standard without failing here. Errors are reported through contract error
+// codes, so a Result around the standard return type is allowed.
+fn spec_function(xdr: &[u8]) -> ScSpecFunctionV0 {
+    match ScSpecEntry::from_xdr(xdr, Limits::none()).unwrap() {
+        ScSpecEntry::FunctionV0(function) => function,
+        entry => panic!("expected a function, got {entry:?}"),
+    }
+}
+
+fn spec_event(xdr: &[u8]) -> ScSpecEventV0 {
+    match ScSpecEntry::from_xdr(xdr, Limits::none()).unwrap() {
+        ScSpecEntry::EventV0(event) => event,
+        entry => panic!("expected an event, got {entry:?}"),
+    }
+}
+
+fn returned_type(function: &ScSpecFunctionV0) -> Option<ScSpecTypeDef> {
+    let output = match function.outputs.first()? {
+        ScSpecTypeDef::Result(result) => (*result.ok_type).clone(),
+        output => output.clone(),
+    };
+    (output != ScSpecTypeDef::Void).then_some(output)
+}
+
+#[test]
+fn test_sep0050_functions_match_spec() {
+    use ScSpecTypeDef::{Address, Bool, String, U32};
+    let optional_address = ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+        value_type: Box::new(Address),
+    }));
+
+    let functions = [
+        (
+            CollectionContract::spec_xdr_balance().to_vec(),
+            "balance",
+            std::vec![Address],
+            Some(U32),
+        ),
+        (
+            CollectionContract::spec_xdr_owner_of().to_vec(),
+            "owner_of",
+            std::vec![U32],
+            Some(Address),
+        ),
+        (
+            CollectionContract::spec_xdr_transfer().to_vec(),
+            "transfer",
+            std::vec![Address, Address, U32],
+            None,
+        ),
+        (
+            CollectionContract::spec_xdr_transfer_from().to_vec(),
+            "transfer_from",
+            std::vec![Address, Address, Address, U32],
+            None,
+        ),
+        (
+            CollectionContract::spec_xdr_approve().to_vec(),
+            "approve",
+            std::vec![Address, Address, U32, U32],
+            None,
+        ),
+        (
+            CollectionContract::spec_xdr_approve_for_all().to_vec(),
+            "approve_for_all",
+            std::vec![Address, Address, U32],
+            None,
+        ),
+        (
+            CollectionContract::spec_xdr_get_approved().to_vec(),
+            "get_approved",
+            std::vec![U32],
+            Some(optional_address),
+        ),
+        (
+            CollectionContract::spec_xdr_is_approved_for_all().to_vec(),
+            "is_approved_for_all",
+            std::vec![Address, Address],
+            Some(Bool),
+        ),
+        (
+            CollectionContract::spec_xdr_name().to_vec(),
+            "name",
+            std::vec![],
+            Some(String),
+        ),
+        (
+            CollectionContract::spec_xdr_symbol().to_vec(),
+            "symbol",
+            std::vec![],
+            Some(String),
+        ),
+        (
+            CollectionContract::spec_xdr_token_uri().to_vec(),
+            "token_uri",
+            std::vec![U32],
+            Some(String),
+        ),
+    ];
+
+    for (xdr, name, arguments, returns) in functions {
+        let function = spec_function(&xdr);
+        assert_eq!(function.name.to_utf8_string_lossy(), name);
+        let actual: std::vec::Vec<_> = function
+            .inputs
+            .iter()
+            .map(|input| input.type_.clone())
+            .collect();
+        assert_eq!(actual, arguments, "{name} arguments");
+        assert_eq!(returned_type(&function), returns, "{name} return type");
+    }
+}
+
+#[test]
+fn test_sep0050_events_match_spec() {
+    use ScSpecEventDataFormat::{SingleValue, Vec};
+    use ScSpecEventParamLocationV0::{Data, TopicList};
+    use ScSpecTypeDef::{Address, U32};
+
+    let events = [
+        (
+            events::Transfer::spec_xdr().to_vec(),
+            "transfer",
+            std::vec![(Address, TopicList), (Address, TopicList), (U32, Data)],
+            SingleValue,
+        ),
+        (
+            events::Approve::spec_xdr().to_vec(),
+            "approve",
+            std::vec![
+                (Address, TopicList),
+                (U32, TopicList),
+                (Address, Data),
+                (U32, Data),
+            ],
+            Vec,
+        ),
+        (
+            events::ApproveForAll::spec_xdr().to_vec(),
+            "approve_for_all",
+            std::vec![(Address, TopicList), (Address, Data), (U32, Data)],
+            Vec,
+        ),
+        (
+            events::Mint::spec_xdr().to_vec(),
+            "mint",
+            std::vec![(Address, TopicList), (U32, Data)],
+            SingleValue,
+        ),
+        (
+            events::Burn::spec_xdr().to_vec(),
+            "burn",
+            std::vec![(Address, TopicList), (U32, Data)],
+            SingleValue,
+        ),
+    ];
+
+    for (xdr, topic, params, data_format) in events {
+        let event = spec_event(&xdr);
+        let prefix: std::vec::Vec<_> = event
+            .prefix_topics
+            .iter()
+            .map(|topic| topic.to_utf8_string_lossy())
+            .collect();
+        assert_eq!(prefix, [topic]);
+        let actual: std::vec::Vec<_> = event
+            .params
+            .iter()
+            .map(|param| (param.type_.clone(), param.location))
+            .collect();
+        assert_eq!(actual, params, "{topic} params");
+        assert_eq!(event.data_format, data_format, "{topic} data format");
+    }
+}
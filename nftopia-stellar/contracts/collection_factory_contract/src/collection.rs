@@ -1,25 +1,60 @@
-use soroban_sdk::{Address, Env, Map, String, Vec};
+use soroban_sdk::{token, Address, Bytes, BytesN, Env, Map, String, Vec};
 
 use crate::{
     errors::Error,
-    storage::{DataKey, RoyaltyInfo, Storage, TokenMetadata},
+    events,
+    factory::Factory,
+    merkle, schema,
+    signature::{self, Permit},
+    storage::{
+        require_current_schema, AttributeDefinition, CollectionConfigUpdate, CollectionInfo,
+        DataKey, MintPhase, MintPrice, MintVoucher, OperatorApproval, PendingTransfer, Role,
+        RoyaltyInfo, RoyaltyShare, Storage, TokenApproval, TokenMetadata, TokenUriConfig,
+        TransferPolicy, BPS_DENOMINATOR, DEFAULT_PHASE, MAX_PAGE_SIZE, MAX_ROYALTY_BPS,
+        MAX_ROYALTY_RECIPIENTS,
+    },
 };
 
 pub struct Collection;
 
 impl Collection {
     // ERC721-like methods
-    pub fn balance_of(env: &Env, collection_id: u64, address: &Address) -> u32 {
-        <DataKey as Storage>::get_balance(env, collection_id, address)
+    pub fn balance_of(env: &Env, collection_id: u64, address: &Address) -> Result<u32, Error> {
+        require_current_schema(env)?;
+        Ok(<DataKey as Storage>::get_balance(
+            env,
+            collection_id,
+            address,
+        ))
     }
 
     pub fn owner_of(env: &Env, collection_id: u64, token_id: u32) -> Result<Address, Error> {
+        require_current_schema(env)?;
         <DataKey as Storage>::get_token_owner(env, collection_id, token_id)
             .ok_or(Error::TokenNotFound)
     }
 
-    pub fn get_approved(env: &Env, collection_id: u64, token_id: u32) -> Option<Address> {
-        <DataKey as Storage>::get_approved(env, collection_id, token_id)
+    // Expired approvals, and those granted before the owner last revoked all
+    // of theirs, read as absent
+    pub fn get_approved(
+        env: &Env,
+        collection_id: u64,
+        token_id: u32,
+    ) -> Result<Option<Address>, Error> {
+        require_current_schema(env)?;
+        let Some(approval) = <DataKey as Storage>::get_token_approval(env, collection_id, token_id)
+        else {
+            return Ok(None);
+        };
+        let Some(owner) = <DataKey as Storage>::get_token_owner(env, collection_id, token_id)
+        else {
+            return Ok(None);
+        };
+
+        Ok(
+            Self::is_approval_live(env, &owner, approval.live_until_ledger, approval.epoch)
+                .then_some(approval.approved),
+        )
     }
 
     pub fn is_approved_for_all(
@@ -27,8 +62,14 @@ impl Collection {
         collection_id: u64,
         owner: &Address,
         operator: &Address,
-    ) -> bool {
-        <DataKey as Storage>::get_approved_for_all(env, collection_id, owner, operator)
+    ) -> Result<bool, Error> {
+        require_current_schema(env)?;
+        Ok(
+            <DataKey as Storage>::get_operator_approval(env, collection_id, owner, operator)
+                .is_some_and(|approval| {
+                    Self::is_approval_live(env, owner, approval.live_until_ledger, approval.epoch)
+                }),
+        )
     }
 
     pub fn approve(
@@ -37,26 +78,207 @@ impl Collection {
         caller: &Address,
         approved: &Address,
         token_id: u32,
+        live_until_ledger: u32,
     ) -> Result<(), Error> {
+        caller.require_auth();
+
         let owner = Self::owner_of(env, collection_id, token_id)?;
 
         // Check if caller is owner or approved for all
-        if &owner != caller && !Self::is_approved_for_all(env, collection_id, &owner, caller) {
+        if &owner != caller && !Self::is_approved_for_all(env, collection_id, &owner, caller)? {
             return Err(Error::NotTokenOwner);
         }
 
-        <DataKey as Storage>::set_approved(env, collection_id, token_id, approved);
+        Self::write_approval(
+            env,
+            collection_id,
+            &owner,
+            approved,
+            token_id,
+            live_until_ledger,
+        )
+    }
+
+    // Stores a token approval once the owner's consent has been checked. A
+    // live_until_ledger of 0 clears the approval instead.
+    pub(crate) fn write_approval(
+        env: &Env,
+        collection_id: u64,
+        owner: &Address,
+        approved: &Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        Self::require_approvals_allowed(env, collection_id)?;
+
+        if live_until_ledger == 0 {
+            <DataKey as Storage>::remove_token_approval(env, collection_id, token_id);
+        } else {
+            Self::require_live_until_ledger(env, live_until_ledger)?;
+
+            let approval = TokenApproval {
+                approved: approved.clone(),
+                live_until_ledger,
+                epoch: <DataKey as Storage>::get_approval_epoch(env, owner),
+            };
+            <DataKey as Storage>::set_token_approval(env, collection_id, token_id, &approval);
+        }
+
+        events::approve(
+            env,
+            collection_id,
+            owner,
+            approved,
+            token_id,
+            live_until_ledger,
+        );
         Ok(())
     }
 
+    // A live_until_ledger of 0 revokes the operator
     pub fn set_approval_for_all(
         env: &Env,
         collection_id: u64,
         owner: &Address,
         operator: &Address,
-        approved: bool,
+        live_until_ledger: u32,
     ) -> Result<(), Error> {
-        <DataKey as Storage>::set_approved_for_all(env, collection_id, owner, operator, approved);
+        owner.require_auth();
+
+        Self::require_approvals_allowed(env, collection_id)?;
+
+        if live_until_ledger == 0 {
+            <DataKey as Storage>::remove_operator_approval(env, collection_id, owner, operator);
+        } else {
+            Self::require_live_until_ledger(env, live_until_ledger)?;
+
+            let approval = OperatorApproval {
+                live_until_ledger,
+                epoch: <DataKey as Storage>::get_approval_epoch(env, owner),
+            };
+            <DataKey as Storage>::set_operator_approval(
+                env,
+                collection_id,
+                owner,
+                operator,
+                &approval,
+            );
+        }
+
+        events::approve_for_all(env, collection_id, owner, operator, live_until_ledger);
+        Ok(())
+    }
+
+    // Voids every token and operator approval the owner has granted, in all
+    // collections, by moving their approval epoch on
+    pub fn revoke_all_approvals(env: &Env, owner: &Address) -> Result<(), Error> {
+        owner.require_auth();
+
+        let epoch = <DataKey as Storage>::get_approval_epoch(env, owner)
+            .checked_add(1)
+            .ok_or(Error::Overflow)?;
+        <DataKey as Storage>::set_approval_epoch(env, owner, epoch);
+
+        events::approvals_revoked(env, owner);
+        Ok(())
+    }
+
+    // Registers the ed25519 key that signs the owner's permits, or removes it.
+    // Contracts cannot generically verify a signature against an account's
+    // signers (custom accounts have none to read, and the auth payload API is
+    // not exposed to contracts), so the key is registered once with the
+    // account's authorization and permits are verified against it instead.
+    pub fn set_permit_key(
+        env: &Env,
+        owner: &Address,
+        public_key: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+
+        match &public_key {
+            Some(public_key) => <DataKey as Storage>::set_permit_key(env, owner, public_key),
+            None => <DataKey as Storage>::remove_permit_key(env, owner),
+        }
+
+        events::permit_key_updated(env, owner, &public_key);
+        Ok(())
+    }
+
+    // Gasless approval: the owner signs a Permit off-chain and anyone can
+    // submit it. The approval it grants lasts until the deadline ledger.
+    // The signature must come from the key registered with set_permit_key;
+    // without one, the owner's permits are rejected.
+    pub fn permit(
+        env: &Env,
+        collection_id: u64,
+        owner: &Address,
+        spender: &Address,
+        token_id: u32,
+        deadline: u32,
+        nonce: u64,
+        public_key: &BytesN<32>,
+        signature: &BytesN<64>,
+    ) -> Result<(), Error> {
+        if deadline < env.ledger().sequence() {
+            return Err(Error::PermitExpired);
+        }
+
+        // Only the key the owner registered can sign for them
+        let permit_key = <DataKey as Storage>::get_permit_key(env, owner);
+        if permit_key.as_ref() != Some(public_key) {
+            return Err(Error::Unauthorized);
+        }
+
+        if &Self::owner_of(env, collection_id, token_id)? != owner {
+            return Err(Error::NotTokenOwner);
+        }
+
+        let expected_nonce = <DataKey as Storage>::get_permit_nonce(env, owner);
+        if nonce != expected_nonce {
+            return Err(Error::InvalidNonce);
+        }
+
+        let permit = Permit {
+            collection_id,
+            owner: owner.clone(),
+            spender: spender.clone(),
+            token_id,
+            deadline,
+            nonce,
+        };
+        env.crypto().ed25519_verify(
+            public_key,
+            &signature::permit_message(env, &permit),
+            signature,
+        );
+
+        let next_nonce = expected_nonce.checked_add(1).ok_or(Error::Overflow)?;
+        <DataKey as Storage>::set_permit_nonce(env, owner, next_nonce);
+
+        Self::write_approval(env, collection_id, owner, spender, token_id, deadline)
+    }
+
+    pub fn permit_nonce(env: &Env, owner: &Address) -> u64 {
+        <DataKey as Storage>::get_permit_nonce(env, owner)
+    }
+
+    pub fn permit_key(env: &Env, owner: &Address) -> Option<BytesN<32>> {
+        <DataKey as Storage>::get_permit_key(env, owner)
+    }
+
+    fn is_approval_live(env: &Env, owner: &Address, live_until_ledger: u32, epoch: u32) -> bool {
+        live_until_ledger >= env.ledger().sequence()
+            && epoch == <DataKey as Storage>::get_approval_epoch(env, owner)
+    }
+
+    // Same bounds as a token allowance: not already expired, and not beyond
+    // the furthest ledger an entry written now can live until
+    fn require_live_until_ledger(env: &Env, live_until_ledger: u32) -> Result<(), Error> {
+        if live_until_ledger < env.ledger().sequence()
+            || live_until_ledger > env.ledger().max_live_until_ledger()
+        {
+            return Err(Error::InvalidLiveUntilLedger);
+        }
         Ok(())
     }
 
@@ -68,26 +290,76 @@ impl Collection {
         to: &Address,
         token_id: u32,
     ) -> Result<(), Error> {
+        caller.require_auth();
+
+        Self::transfer_token(env, collection_id, caller, from, to, token_id)
+    }
+
+    // Moves a token once the caller's authorization has been checked
+    fn transfer_token(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        from: &Address,
+        to: &Address,
+        token_id: u32,
+    ) -> Result<(), Error> {
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        Self::require_collection_contract(env, &info);
+
+        let admin_transfer = match info.config.transfer_policy {
+            TransferPolicy::Transferable => false,
+            TransferPolicy::Soulbound => return Err(Error::TransferRestricted),
+            TransferPolicy::AdminTransferOnly if caller == &info.admin => true,
+            TransferPolicy::AdminTransferOnly => return Err(Error::TransferRestricted),
+        };
+
         // Check ownership or approval
         let owner = Self::owner_of(env, collection_id, token_id)?;
         if &owner != from {
             return Err(Error::NotTokenOwner);
         }
 
-        let approved = Self::get_approved(env, collection_id, token_id);
-        if caller != from
+        let approved = Self::get_approved(env, collection_id, token_id)?;
+        if !admin_transfer
+            && caller != from
             && approved.as_ref() != Some(caller)
-            && !Self::is_approved_for_all(env, collection_id, from, caller)
+            && !Self::is_approved_for_all(env, collection_id, from, caller)?
         {
             return Err(Error::NotApproved);
         }
 
         // Perform transfer
         <DataKey as Storage>::set_token_owner(env, collection_id, token_id, to);
+        Self::remove_token_from_owner(env, collection_id, from, token_id);
         <DataKey as Storage>::decrement_balance(env, collection_id, from);
+        Self::add_token_to_owner(env, collection_id, to, token_id);
         <DataKey as Storage>::increment_balance(env, collection_id, to);
-        <DataKey as Storage>::remove_approved(env, collection_id, token_id);
+        <DataKey as Storage>::remove_token_approval(env, collection_id, token_id);
+
+        events::transfer(env, collection_id, from, to, token_id);
+        Ok(())
+    }
 
+    // A collection with its own contract takes token calls through it, so the
+    // SEP-0050 events published there cover every mint, transfer, approval
+    // and burn. Collections from before per-collection contracts are
+    // addressed by the factory itself.
+    fn require_collection_contract(env: &Env, info: &CollectionInfo) {
+        if info.address != env.current_contract_address() {
+            info.address.require_auth();
+        }
+    }
+
+    // Approvals only make sense for collections whose holders can transfer,
+    // and are granted through the collection contract like transfers
+    fn require_approvals_allowed(env: &Env, collection_id: u64) -> Result<(), Error> {
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        Self::require_collection_contract(env, &info);
+
+        if info.config.transfer_policy != TransferPolicy::Transferable {
+            return Err(Error::TransferRestricted);
+        }
         Ok(())
     }
 
@@ -99,46 +371,818 @@ impl Collection {
         address: &Address,
         whitelisted: bool,
     ) -> Result<(), Error> {
-        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
-        if &info.creator != caller {
-            return Err(Error::Unauthorized);
+        Self::require_role(env, collection_id, caller, Role::WhitelistManager)?;
+
+        <DataKey as Storage>::set_whitelisted_for_mint(env, collection_id, address, whitelisted);
+
+        events::whitelist_updated(env, collection_id, address, whitelisted);
+        Ok(())
+    }
+
+    // Mint price charged when no mint phases are scheduled
+    pub fn set_mint_price(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        price: Option<MintPrice>,
+    ) -> Result<(), Error> {
+        Self::require_admin(env, collection_id, caller)?;
+
+        match &price {
+            Some(price) => {
+                Self::validate_mint_price(env, &price.token, price.amount)?;
+                <DataKey as Storage>::set_mint_price(env, collection_id, price);
+            }
+            None => <DataKey as Storage>::remove_mint_price(env, collection_id),
+        }
+        Self::update_paid_mints(env, collection_id);
+
+        events::mint_price_updated(env, collection_id, price);
+        Ok(())
+    }
+
+    // Keeps the factory's count of collections charging for mints in step
+    // with their prices, so the fee token cannot change under them
+    fn update_paid_mints(env: &Env, collection_id: u64) {
+        let paid = <DataKey as Storage>::get_mint_price(env, collection_id).is_some()
+            || <DataKey as Storage>::get_mint_phases(env, collection_id)
+                .iter()
+                .any(|phase| phase.price > 0);
+        if paid == <DataKey as Storage>::has_paid_mints(env, collection_id) {
+            return;
+        }
+
+        let count = <DataKey as Storage>::get_paid_mint_collections(env);
+        let count = if paid { count + 1 } else { count - 1 };
+        <DataKey as Storage>::set_has_paid_mints(env, collection_id, paid);
+        <DataKey as Storage>::set_paid_mint_collections(env, count);
+    }
+
+    pub fn mint_price(env: &Env, collection_id: u64) -> Option<MintPrice> {
+        <DataKey as Storage>::get_mint_price(env, collection_id)
+    }
+
+    // Mint phases. Phase IDs are the 1-based position in the schedule, which
+    // keys the phase's allowlist root and mint counters. Every call starts a
+    // new schedule, so its phases count mints from zero and need their roots
+    // set again, even where they reuse an earlier schedule's phase IDs.
+    pub fn set_mint_phases(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        phases: Vec<MintPhase>,
+    ) -> Result<(), Error> {
+        Self::require_admin(env, collection_id, caller)?;
+
+        let mut previous_end = 0;
+        for phase in phases.iter() {
+            if phase.start_time >= phase.end_time || phase.start_time < previous_end {
+                return Err(Error::InvalidConfig);
+            }
+            if phase.price < 0 {
+                return Err(Error::InvalidConfig);
+            }
+            if phase.price > 0 {
+                let price_token = phase.price_token.as_ref().ok_or(Error::InvalidConfig)?;
+                Self::validate_mint_price(env, price_token, phase.price)?;
+            }
+            previous_end = phase.end_time;
+        }
+
+        let schedule = <DataKey as Storage>::get_phase_schedule(env, collection_id);
+        <DataKey as Storage>::set_phase_schedule(env, collection_id, schedule + 1);
+        <DataKey as Storage>::set_mint_phases(env, collection_id, &phases);
+        Self::update_paid_mints(env, collection_id);
+
+        events::mint_phases_updated(env, collection_id, phases.len());
+        Ok(())
+    }
+
+    pub fn mint_phases(env: &Env, collection_id: u64) -> Vec<MintPhase> {
+        <DataKey as Storage>::get_mint_phases(env, collection_id)
+    }
+
+    pub fn active_phase(env: &Env, collection_id: u64) -> Option<u32> {
+        Self::select_phase(env, collection_id)
+            .ok()
+            .flatten()
+            .map(|(phase_id, _)| phase_id)
+    }
+
+    // Returns the phase open at the current ledger timestamp, None when the
+    // collection has no schedule, or NoActivePhase between scheduled phases
+    fn select_phase(env: &Env, collection_id: u64) -> Result<Option<(u32, MintPhase)>, Error> {
+        let phases = <DataKey as Storage>::get_mint_phases(env, collection_id);
+        if phases.is_empty() {
+            return Ok(None);
+        }
+
+        let now = env.ledger().timestamp();
+        for (index, phase) in phases.iter().enumerate() {
+            if phase.start_time <= now && now < phase.end_time {
+                return Ok(Some((index as u32 + 1, phase)));
+            }
+        }
+
+        Err(Error::NoActivePhase)
+    }
+
+    // Merkle allowlist. Roots apply to a phase of the current schedule, or to
+    // DEFAULT_PHASE while none is set, and are dropped by set_mint_phases.
+    pub fn set_merkle_root(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        phase: u32,
+        root: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::WhitelistManager)?;
+
+        <DataKey as Storage>::set_merkle_root(env, collection_id, phase, &root);
+
+        events::merkle_root_updated(env, collection_id, phase, &root);
+        Ok(())
+    }
+
+    pub fn merkle_root(env: &Env, collection_id: u64, phase: u32) -> Option<BytesN<32>> {
+        <DataKey as Storage>::get_merkle_root(env, collection_id, phase)
+    }
+
+    pub fn allowlist_minted(env: &Env, collection_id: u64, phase: u32, address: &Address) -> u32 {
+        <DataKey as Storage>::get_allowlist_minted(env, collection_id, phase, address)
+    }
+
+    // Token URI
+    pub fn token_uri(env: &Env, collection_id: u64, token_id: u32) -> Result<String, Error> {
+        require_current_schema(env)?;
+        let metadata = <DataKey as Storage>::get_token_metadata(env, collection_id, token_id)
+            .ok_or(Error::TokenNotFound)?;
+
+        let Some(config) = <DataKey as Storage>::get_token_uri_config(env, collection_id) else {
+            return Ok(metadata.uri);
+        };
+
+        if let Some(placeholder_uri) = config.placeholder_uri {
+            if !config.revealed {
+                return Ok(placeholder_uri);
+            }
+        }
+
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        let mut uri = info.config.base_uri.to_bytes();
+        uri.append(&decimal_bytes(env, token_id));
+        uri.append(&config.suffix.to_bytes());
+        Ok(uri.to_string())
+    }
+
+    // Token metadata
+    pub fn token_metadata(
+        env: &Env,
+        collection_id: u64,
+        token_id: u32,
+    ) -> Result<TokenMetadata, Error> {
+        require_current_schema(env)?;
+        <DataKey as Storage>::get_token_metadata(env, collection_id, token_id)
+            .ok_or(Error::TokenNotFound)
+    }
+
+    // Metadata updates
+    pub fn update_token_metadata(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        token_id: u32,
+        uri: String,
+        attributes: Map<String, String>,
+    ) -> Result<(), Error> {
+        let info = Self::require_role(env, collection_id, caller, Role::MetadataEditor)?;
+
+        let mut metadata = <DataKey as Storage>::get_token_metadata(env, collection_id, token_id)
+            .ok_or(Error::TokenNotFound)?;
+
+        if Self::is_metadata_frozen(env, collection_id, Some(token_id)) {
+            return Err(Error::MetadataFrozen);
+        }
+
+        Self::validate_attributes(env, collection_id, &info, &attributes)?;
+
+        metadata.uri = uri;
+        metadata.attributes = attributes;
+        metadata.updated_at = Some(env.ledger().timestamp());
+        <DataKey as Storage>::set_token_metadata(env, collection_id, token_id, &metadata);
+
+        events::metadata_updated(env, collection_id, Some(token_id));
+        Ok(())
+    }
+
+    pub fn set_base_uri(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        base_uri: String,
+    ) -> Result<(), Error> {
+        let mut info = Self::require_role(env, collection_id, caller, Role::MetadataEditor)?;
+
+        if Self::is_metadata_frozen(env, collection_id, None) {
+            return Err(Error::MetadataFrozen);
+        }
+
+        info.config.base_uri = base_uri;
+        <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+
+        events::metadata_updated(env, collection_id, None);
+        Ok(())
+    }
+
+    // Switches token_uri to base_uri + token id + suffix. Must be configured
+    // before the first mint so the provenance hash is committed up front.
+    pub fn set_token_uri_config(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        suffix: String,
+        placeholder_uri: Option<String>,
+        provenance_hash: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::MetadataEditor)?;
+
+        if <DataKey as Storage>::get_next_token_id(env, collection_id) > 1 {
+            return Err(Error::MintStarted);
+        }
+        if <DataKey as Storage>::get_token_uri_config(env, collection_id)
+            .is_some_and(|config| config.revealed)
+        {
+            return Err(Error::AlreadyRevealed);
+        }
+        if Self::is_metadata_frozen(env, collection_id, None) {
+            return Err(Error::MetadataFrozen);
+        }
+
+        let config = TokenUriConfig {
+            suffix,
+            placeholder_uri,
+            provenance_hash,
+            revealed: false,
+        };
+        <DataKey as Storage>::set_token_uri_config(env, collection_id, &config);
+
+        events::token_uri_configured(env, collection_id, &config.provenance_hash);
+        Ok(())
+    }
+
+    pub fn token_uri_config(env: &Env, collection_id: u64) -> Option<TokenUriConfig> {
+        <DataKey as Storage>::get_token_uri_config(env, collection_id)
+    }
+
+    // Replaces the placeholder with URIs derived from revealed_base_uri for
+    // every token at once. Can only happen once.
+    pub fn reveal(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        revealed_base_uri: String,
+    ) -> Result<(), Error> {
+        let mut info = Self::require_role(env, collection_id, caller, Role::MetadataEditor)?;
+
+        let mut config = <DataKey as Storage>::get_token_uri_config(env, collection_id)
+            .filter(|config| config.placeholder_uri.is_some())
+            .ok_or(Error::InvalidConfig)?;
+
+        if config.revealed {
+            return Err(Error::AlreadyRevealed);
+        }
+        if Self::is_metadata_frozen(env, collection_id, None) {
+            return Err(Error::MetadataFrozen);
+        }
+
+        config.revealed = true;
+        <DataKey as Storage>::set_token_uri_config(env, collection_id, &config);
+
+        info.config.base_uri = revealed_base_uri;
+        <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+
+        events::revealed(env, collection_id, &info.config.base_uri);
+        Ok(())
+    }
+
+    // Declares the attribute keys, and for Advanced collections the value
+    // types, that minted and updated tokens must conform to
+    pub fn set_attribute_schema(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        definitions: Vec<AttributeDefinition>,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::MetadataEditor)?;
+
+        schema::validate_definitions(&definitions)?;
+        <DataKey as Storage>::set_attribute_schema(env, collection_id, &definitions);
+
+        events::attribute_schema_updated(env, collection_id, definitions.len());
+        Ok(())
+    }
+
+    pub fn attribute_schema(env: &Env, collection_id: u64) -> Vec<AttributeDefinition> {
+        <DataKey as Storage>::get_attribute_schema(env, collection_id)
+    }
+
+    fn validate_attributes(
+        env: &Env,
+        collection_id: u64,
+        info: &CollectionInfo,
+        attributes: &Map<String, String>,
+    ) -> Result<(), Error> {
+        let definitions = <DataKey as Storage>::get_attribute_schema(env, collection_id);
+        schema::validate_attributes(&info.config.metadata_schema, &definitions, attributes)
+    }
+
+    // Permanently blocks metadata changes to one token, or to the whole
+    // collection when token_id is None
+    pub fn freeze_metadata(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        token_id: Option<u32>,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::MetadataEditor)?;
+
+        match token_id {
+            Some(token_id) => {
+                if <DataKey as Storage>::get_token_metadata(env, collection_id, token_id).is_none()
+                {
+                    return Err(Error::TokenNotFound);
+                }
+                <DataKey as Storage>::set_token_metadata_frozen(env, collection_id, token_id);
+            }
+            None => <DataKey as Storage>::set_metadata_frozen(env, collection_id),
+        }
+
+        events::metadata_frozen(env, collection_id, token_id);
+        Ok(())
+    }
+
+    // Whether the token's metadata, or the collection's when token_id is
+    // None, can no longer change
+    pub fn is_metadata_frozen(env: &Env, collection_id: u64, token_id: Option<u32>) -> bool {
+        <DataKey as Storage>::is_metadata_frozen(env, collection_id)
+            || token_id.is_some_and(|token_id| {
+                <DataKey as Storage>::is_token_metadata_frozen(env, collection_id, token_id)
+            })
+    }
+
+    // Total supply
+    pub fn total_supply(env: &Env, collection_id: u64) -> Result<u32, Error> {
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        Ok(info.total_tokens)
+    }
+
+    // Royalty info
+    pub fn royalty_info(env: &Env, collection_id: u64) -> Option<RoyaltyInfo> {
+        <DataKey as Storage>::get_royalty_info(env, collection_id)
+    }
+
+    pub fn token_royalty_info(env: &Env, collection_id: u64, token_id: u32) -> Option<RoyaltyInfo> {
+        <DataKey as Storage>::get_token_royalty_info(env, collection_id, token_id)
+    }
+
+    pub fn royalty_splits(env: &Env, collection_id: u64) -> Vec<RoyaltyShare> {
+        <DataKey as Storage>::get_royalty_splits(env, collection_id)
+    }
+
+    // Royalty owed on a sale of token_id, using the token's override when set
+    // and the collection default otherwise. Amounts round down. A single
+    // recipient cannot describe a split table, so collections with one fail
+    // with RoyaltySplitsActive; royalty_payouts_for_sale is authoritative.
+    pub fn royalty_info_for_sale(
+        env: &Env,
+        collection_id: u64,
+        token_id: u32,
+        sale_price: i128,
+    ) -> Result<(Address, i128), Error> {
+        let (royalty, amount, splits) =
+            Self::sale_royalty(env, collection_id, token_id, sale_price)?;
+        if !splits.is_empty() {
+            return Err(Error::RoyaltySplitsActive);
+        }
+        Ok((royalty.recipient, amount))
+    }
+
+    // Royalty owed on a sale of token_id, split across the collection's
+    // recipients. Each share rounds down and the remainder goes to the first
+    // recipient, so payouts always add up to the royalty amount.
+    pub fn royalty_payouts_for_sale(
+        env: &Env,
+        collection_id: u64,
+        token_id: u32,
+        sale_price: i128,
+    ) -> Result<Vec<(Address, i128)>, Error> {
+        let (royalty, amount, splits) =
+            Self::sale_royalty(env, collection_id, token_id, sale_price)?;
+
+        let mut payouts = Vec::new(env);
+        if splits.is_empty() {
+            payouts.push_back((royalty.recipient, amount));
+            return Ok(payouts);
+        }
+
+        let mut remainder = amount;
+        for split in splits.iter() {
+            let payout = sale_price * split.share as i128 / BPS_DENOMINATOR;
+            remainder -= payout;
+            payouts.push_back((split.recipient, payout));
+        }
+
+        let (first_recipient, first_payout) = payouts.get_unchecked(0);
+        payouts.set(0, (first_recipient, first_payout + remainder));
+        Ok(payouts)
+    }
+
+    // The royalty that applies to token_id, its amount for sale_price, and the
+    // collection split table when no per-token override takes precedence
+    fn sale_royalty(
+        env: &Env,
+        collection_id: u64,
+        token_id: u32,
+        sale_price: i128,
+    ) -> Result<(RoyaltyInfo, i128, Vec<RoyaltyShare>), Error> {
+        if sale_price < 0 {
+            return Err(Error::InvalidInput);
+        }
+        if <DataKey as Storage>::get_token_owner(env, collection_id, token_id).is_none() {
+            return Err(Error::TokenNotFound);
+        }
+
+        let (royalty, splits) =
+            match <DataKey as Storage>::get_token_royalty_info(env, collection_id, token_id) {
+                Some(royalty) => (royalty, Vec::new(env)),
+                None => {
+                    let royalty = match <DataKey as Storage>::get_royalty_info(env, collection_id) {
+                        Some(royalty) => royalty,
+                        None => {
+                            let info =
+                                <DataKey as Storage>::get_collection_info(env, collection_id)?;
+                            RoyaltyInfo {
+                                recipient: info.config.royalty_recipient,
+                                percentage: info.config.royalty_percentage,
+                            }
+                        }
+                    };
+                    (
+                        royalty,
+                        <DataKey as Storage>::get_royalty_splits(env, collection_id),
+                    )
+                }
+            };
+
+        let amount = sale_price
+            .checked_mul(royalty.percentage as i128)
+            .ok_or(Error::Overflow)?
+            / BPS_DENOMINATOR;
+        Ok((royalty, amount, splits))
+    }
+
+    // Enumeration
+    pub fn tokens_of_owner(
+        env: &Env,
+        collection_id: u64,
+        owner: &Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<u32>, Error> {
+        require_current_schema(env)?;
+        let balance = <DataKey as Storage>::get_balance(env, collection_id, owner);
+        let end = balance.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut token_ids = Vec::new(env);
+        for index in start..end {
+            if let Some(token_id) =
+                <DataKey as Storage>::get_owner_token(env, collection_id, owner, index)
+            {
+                token_ids.push_back(token_id);
+            }
+        }
+        Ok(token_ids)
+    }
+
+    pub fn token_by_index(env: &Env, collection_id: u64, index: u32) -> Result<u32, Error> {
+        <DataKey as Storage>::get_token_by_index(env, collection_id, index)
+            .ok_or(Error::TokenNotFound)
+    }
+
+    pub fn tokens(
+        env: &Env,
+        collection_id: u64,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Vec<u32>, Error> {
+        let total = Self::total_supply(env, collection_id)?;
+        let end = total.min(cursor.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut token_ids = Vec::new(env);
+        for index in cursor..end {
+            if let Some(token_id) =
+                <DataKey as Storage>::get_token_by_index(env, collection_id, index)
+            {
+                token_ids.push_back(token_id);
+            }
+        }
+        Ok(token_ids)
+    }
+
+    // Mint a new token
+    pub fn mint(
+        env: &Env,
+        collection_id: u64,
+        to: &Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> Result<u32, Error> {
+        to.require_auth();
+
+        let phase = Self::select_phase(env, collection_id)?;
+        Self::mint_in_phase(env, collection_id, to, uri, attributes, phase, false)
+    }
+
+    // Mint on behalf of a holder of the Minter role, bypassing mint phases and
+    // the public mint and whitelist checks
+    pub fn minter_mint(
+        env: &Env,
+        collection_id: u64,
+        minter: &Address,
+        to: &Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> Result<u32, Error> {
+        Self::require_role(env, collection_id, minter, Role::Minter)?;
+
+        Self::mint_token(env, collection_id, to, uri, attributes)
+    }
+
+    // Authorizes or revokes an ed25519 key that signs the collection's mint
+    // vouchers
+    pub fn set_minter_key(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        public_key: &BytesN<32>,
+        authorized: bool,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::Minter)?;
+
+        if authorized {
+            <DataKey as Storage>::set_minter_key(env, collection_id, public_key);
+        } else {
+            <DataKey as Storage>::remove_minter_key(env, collection_id, public_key);
+        }
+
+        events::minter_key_updated(env, collection_id, public_key, authorized);
+        Ok(())
+    }
+
+    pub fn is_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>) -> bool {
+        <DataKey as Storage>::is_minter_key(env, collection_id, public_key)
+    }
+
+    // Lazy minting: mints the token described by a voucher signed with one of
+    // the collection's minter keys to the buyer, who pays the voucher price.
+    // Like minter_mint, the signer's authority stands in for mint phases, so
+    // phase windows, phase caps, allowlists and the public mint checks do not
+    // apply; the pause flag, max_supply and the attribute schema still do. A
+    // creator running a capped drop issues no more vouchers than the cap.
+    pub fn redeem_voucher(
+        env: &Env,
+        collection_id: u64,
+        voucher: MintVoucher,
+        signature: &BytesN<64>,
+        buyer: &Address,
+    ) -> Result<u32, Error> {
+        buyer.require_auth();
+
+        if env.ledger().timestamp() > voucher.expires_at {
+            return Err(Error::VoucherExpired);
+        }
+        if voucher.price < 0 {
+            return Err(Error::InvalidInput);
+        }
+
+        // A voucher that could never be paid for is rejected before anything
+        // is verified or minted
+        let price_token = if voucher.price > 0 {
+            let price_token = voucher.price_token.clone().ok_or(Error::InvalidConfig)?;
+            Self::validate_mint_price(env, &price_token, voucher.price)?;
+            Some(price_token)
+        } else {
+            None
+        };
+
+        if <DataKey as Storage>::is_voucher_redeemed(env, collection_id, &voucher.voucher_id) {
+            return Err(Error::VoucherAlreadyRedeemed);
+        }
+        if !<DataKey as Storage>::is_minter_key(env, collection_id, &voucher.signer) {
+            return Err(Error::Unauthorized);
+        }
+
+        env.crypto().ed25519_verify(
+            &voucher.signer,
+            &signature::voucher_message(env, collection_id, &voucher),
+            signature,
+        );
+        <DataKey as Storage>::set_voucher_redeemed(env, collection_id, &voucher.voucher_id);
+
+        let token_id = Self::mint_token(
+            env,
+            collection_id,
+            buyer,
+            voucher.uri,
+            Some(voucher.attributes),
+        )?;
+
+        if let Some(price_token) = price_token {
+            let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+            Self::charge_mint_price(
+                env,
+                collection_id,
+                &info,
+                buyer,
+                &price_token,
+                voucher.price,
+            )?;
+        }
+
+        events::voucher_redeemed(env, collection_id, &voucher.voucher_id, buyer, token_id);
+        Ok(token_id)
+    }
+
+    pub fn is_voucher_redeemed(env: &Env, collection_id: u64, voucher_id: &BytesN<32>) -> bool {
+        <DataKey as Storage>::is_voucher_redeemed(env, collection_id, voucher_id)
+    }
+
+    // Mint as a member of the collection's Merkle allowlist. The leaf commits
+    // to `to` and `allowance`, the number of tokens `to` may mint in the phase.
+    pub fn mint_with_proof(
+        env: &Env,
+        collection_id: u64,
+        to: &Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+        proof: Vec<BytesN<32>>,
+        allowance: u32,
+    ) -> Result<u32, Error> {
+        to.require_auth();
+
+        let phase = Self::select_phase(env, collection_id)?;
+        let phase_id = phase.as_ref().map_or(DEFAULT_PHASE, |(id, _)| *id);
+
+        let root = <DataKey as Storage>::get_merkle_root(env, collection_id, phase_id)
+            .ok_or(Error::InvalidProof)?;
+        if !merkle::verify(env, &root, merkle::leaf(env, to, allowance), &proof) {
+            return Err(Error::InvalidProof);
+        }
+
+        let minted = <DataKey as Storage>::get_allowlist_minted(env, collection_id, phase_id, to);
+        if minted >= allowance {
+            return Err(Error::AllowanceExceeded);
+        }
+        <DataKey as Storage>::set_allowlist_minted(env, collection_id, phase_id, to, minted + 1);
+
+        Self::mint_in_phase(env, collection_id, to, uri, attributes, phase, true)
+    }
+
+    // Applies the mint rules of `phase`, or the collection's public mint and
+    // whitelist settings when no phases are scheduled, then mints the token.
+    // `allowlisted` is set for callers already cleared by an allowlist proof.
+    fn mint_in_phase(
+        env: &Env,
+        collection_id: u64,
+        to: &Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+        phase: Option<(u32, MintPhase)>,
+        allowlisted: bool,
+    ) -> Result<u32, Error> {
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        let whitelisted =
+            allowlisted || <DataKey as Storage>::is_whitelisted_for_mint(env, collection_id, to);
+
+        let Some((phase_id, phase)) = phase else {
+            if !info.config.is_public_mint && !whitelisted {
+                return Err(Error::WhitelistRequired);
+            }
+
+            let token_id = Self::mint_token(env, collection_id, to, uri, attributes)?;
+            if let Some(price) = <DataKey as Storage>::get_mint_price(env, collection_id) {
+                Self::charge_mint_price(env, collection_id, &info, to, &price.token, price.amount)?;
+            }
+            return Ok(token_id);
+        };
+
+        if phase.requires_allowlist && !whitelisted {
+            return Err(Error::WhitelistRequired);
+        }
+
+        let phase_minted = <DataKey as Storage>::get_phase_minted(env, collection_id, phase_id);
+        if let Some(supply_cap) = phase.supply_cap {
+            if phase_minted >= supply_cap {
+                return Err(Error::MaxSupplyExceeded);
+            }
+        }
+
+        let wallet_minted =
+            <DataKey as Storage>::get_phase_wallet_minted(env, collection_id, phase_id, to);
+        if let Some(wallet_limit) = phase.wallet_limit {
+            if wallet_minted >= wallet_limit {
+                return Err(Error::WalletLimitReached);
+            }
+        }
+
+        let token_id = Self::mint_token(env, collection_id, to, uri, attributes)?;
+
+        <DataKey as Storage>::set_phase_minted(env, collection_id, phase_id, phase_minted + 1);
+        <DataKey as Storage>::set_phase_wallet_minted(
+            env,
+            collection_id,
+            phase_id,
+            to,
+            wallet_minted + 1,
+        );
+
+        if phase.price > 0 {
+            if let Some(price_token) = phase.price_token {
+                Self::charge_mint_price(env, collection_id, &info, to, &price_token, phase.price)?;
+            }
+        }
+
+        Ok(token_id)
+    }
+
+    // Pulls a mint payment from the minter: the platform's basis-point cut goes
+    // to the factory treasury and the rest to the collection admin
+    fn charge_mint_price(
+        env: &Env,
+        collection_id: u64,
+        info: &CollectionInfo,
+        payer: &Address,
+        price_token: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let mut factory_config = <DataKey as Storage>::get_factory_config(env)?;
+
+        // Platform fees accumulate in the factory fee token only
+        if factory_config.fee_token.as_ref() != Some(price_token) {
+            return Err(Error::InvalidConfig);
         }
 
-        <DataKey as Storage>::set_whitelisted_for_mint(env, collection_id, address, whitelisted);
+        let platform_share = amount
+            .checked_mul(factory_config.platform_fee_bps as i128)
+            .ok_or(Error::Overflow)?
+            / BPS_DENOMINATOR;
+        let creator_share = amount - platform_share;
 
-        Ok(())
-    }
+        let client = token::Client::new(env, price_token);
+        if creator_share > 0 {
+            client.transfer(payer, &info.creator, &creator_share);
+        }
+        if platform_share > 0 {
+            client.transfer(payer, env.current_contract_address(), &platform_share);
 
-    // Token URI
-    pub fn token_uri(env: &Env, collection_id: u64, token_id: u32) -> Result<String, Error> {
-        let metadata = <DataKey as Storage>::get_token_metadata(env, collection_id, token_id)
-            .ok_or(Error::TokenNotFound)?;
-        Ok(metadata.uri)
-    }
+            factory_config.accumulated_fees = factory_config
+                .accumulated_fees
+                .checked_add(platform_share)
+                .ok_or(Error::Overflow)?;
+            <DataKey as Storage>::set_factory_config(env, &factory_config);
+        }
 
-    // Token metadata
-    pub fn token_metadata(
-        env: &Env,
-        collection_id: u64,
-        token_id: u32,
-    ) -> Result<TokenMetadata, Error> {
-        <DataKey as Storage>::get_token_metadata(env, collection_id, token_id)
-            .ok_or(Error::TokenNotFound)
+        events::mint_paid(
+            env,
+            collection_id,
+            payer,
+            price_token,
+            amount,
+            platform_share,
+        );
+        Ok(())
     }
 
-    // Total supply
-    pub fn total_supply(env: &Env, collection_id: u64) -> Result<u32, Error> {
-        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
-        Ok(info.total_tokens)
-    }
+    // Mint prices must be positive and in the factory fee token, so that the
+    // platform share can be paid out by withdraw_fees
+    fn validate_mint_price(env: &Env, price_token: &Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Err(Error::InvalidConfig);
+        }
 
-    // Royalty info
-    pub fn royalty_info(env: &Env, collection_id: u64) -> Option<RoyaltyInfo> {
-        <DataKey as Storage>::get_royalty_info(env, collection_id)
+        let fee_token = <DataKey as Storage>::get_factory_config(env)?
+            .fee_token
+            .ok_or(Error::FeeTokenNotSet)?;
+        if &fee_token != price_token {
+            return Err(Error::InvalidConfig);
+        }
+
+        Ok(())
     }
 
-    // Mint a new token
-    pub fn mint(
+    // Mints a token once the caller's authorization and mint rules have been
+    // checked
+    fn mint_token(
         env: &Env,
         collection_id: u64,
         to: &Address,
@@ -150,6 +1194,7 @@ impl Collection {
         }
 
         let mut info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        Self::require_collection_contract(env, &info);
 
         if let Some(max_supply) = info.config.max_supply {
             if info.total_tokens >= max_supply {
@@ -157,23 +1202,22 @@ impl Collection {
             }
         }
 
-        if !info.config.is_public_mint
-            && !<DataKey as Storage>::is_whitelisted_for_mint(env, collection_id, to)
-        {
-            return Err(Error::WhitelistRequired);
-        }
+        let attributes = attributes.unwrap_or_else(|| Map::new(env));
+        Self::validate_attributes(env, collection_id, &info, &attributes)?;
 
         let token_id = <DataKey as Storage>::get_next_token_id(env, collection_id);
 
         let metadata = TokenMetadata {
             token_id,
             uri: uri.clone(),
-            attributes: attributes.unwrap_or_else(|| Map::new(env)),
+            attributes,
             creator: to.clone(),
             created_at: env.ledger().timestamp(),
             updated_at: None,
         };
 
+        Self::add_token_to_owner(env, collection_id, to, token_id);
+        Self::add_token_to_all(env, collection_id, info.total_tokens, token_id);
         <DataKey as Storage>::set_token_owner(env, collection_id, token_id, to);
         <DataKey as Storage>::set_token_metadata(env, collection_id, token_id, &metadata);
         <DataKey as Storage>::increment_balance(env, collection_id, to);
@@ -182,6 +1226,7 @@ impl Collection {
         info.total_tokens += 1;
         <DataKey as Storage>::set_collection_info(env, collection_id, &info);
 
+        events::mint(env, collection_id, to, token_id);
         Ok(token_id)
     }
 
@@ -193,14 +1238,24 @@ impl Collection {
         uris: Vec<String>,
         attributes_list: Option<Vec<Map<String, String>>>,
     ) -> Result<Vec<u32>, Error> {
-        let _start_token_id = <DataKey as Storage>::get_next_token_id(env, collection_id);
+        to.require_auth();
+
+        let phase = Self::select_phase(env, collection_id)?;
         let mut token_ids = Vec::new(env);
 
         for i in 0..uris.len() {
             let uri = uris.get(i).unwrap();
             let attrs = attributes_list.as_ref().and_then(|v| v.get(i));
 
-            let token_id = Self::mint(env, collection_id, to, uri.clone(), attrs)?;
+            let token_id = Self::mint_in_phase(
+                env,
+                collection_id,
+                to,
+                uri.clone(),
+                attrs,
+                phase.clone(),
+                false,
+            )?;
             token_ids.push_back(token_id);
         }
 
@@ -215,7 +1270,9 @@ impl Collection {
         to: &Address,
         token_id: u32,
     ) -> Result<(), Error> {
-        Self::transfer_from(env, collection_id, from, from, to, token_id)
+        from.require_auth();
+
+        Self::transfer_token(env, collection_id, from, from, to, token_id)
     }
 
     // Batch transfer - FIXED: use token_id directly
@@ -226,8 +1283,10 @@ impl Collection {
         to: &Address,
         token_ids: Vec<u32>,
     ) -> Result<(), Error> {
+        from.require_auth();
+
         for token_id in token_ids.iter() {
-            Self::transfer(env, collection_id, from, to, token_id)?;
+            Self::transfer_token(env, collection_id, from, from, to, token_id)?;
         }
         Ok(())
     }
@@ -239,6 +1298,8 @@ impl Collection {
         owner: &Address,
         token_id: u32,
     ) -> Result<(), Error> {
+        owner.require_auth();
+
         let token_owner = <DataKey as Storage>::get_token_owner(env, collection_id, token_id)
             .ok_or(Error::TokenNotFound)?;
 
@@ -246,14 +1307,20 @@ impl Collection {
             return Err(Error::NotTokenOwner);
         }
 
+        let mut info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        Self::require_collection_contract(env, &info);
+
+        Self::remove_token_from_owner(env, collection_id, owner, token_id);
+        Self::remove_token_from_all(env, collection_id, info.total_tokens, token_id);
         <DataKey as Storage>::remove_token_owner(env, collection_id, token_id);
-        <DataKey as Storage>::remove_approved(env, collection_id, token_id);
+        <DataKey as Storage>::remove_token_approval(env, collection_id, token_id);
+        <DataKey as Storage>::remove_token_royalty_info(env, collection_id, token_id);
         <DataKey as Storage>::decrement_balance(env, collection_id, owner);
 
-        let mut info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
         info.total_tokens = info.total_tokens.saturating_sub(1);
         <DataKey as Storage>::set_collection_info(env, collection_id, &info);
 
+        events::burn(env, collection_id, owner, token_id);
         Ok(())
     }
 
@@ -265,13 +1332,16 @@ impl Collection {
         recipient: Address,
         percentage: u32,
     ) -> Result<(), Error> {
-        if percentage > 2500 {
+        Self::require_role(env, collection_id, caller, Role::RoyaltyAdmin)?;
+
+        if percentage > MAX_ROYALTY_BPS {
             return Err(Error::InvalidRoyaltyPercentage);
         }
 
-        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
-        if &info.creator != caller {
-            return Err(Error::Unauthorized);
+        // An existing split table must keep adding up to the total royalty
+        let splits = <DataKey as Storage>::get_royalty_splits(env, collection_id);
+        if !splits.is_empty() && Self::split_total(&splits) != percentage {
+            return Err(Error::RoyaltySplitMismatch);
         }
 
         let royalty = RoyaltyInfo {
@@ -280,9 +1350,194 @@ impl Collection {
         };
         <DataKey as Storage>::set_royalty_info(env, collection_id, &royalty);
 
+        events::royalty_updated(env, collection_id, &recipient, percentage);
+        Ok(())
+    }
+
+    // Divides the collection royalty between several recipients. Shares must
+    // add up to the collection's royalty percentage; an empty table removes
+    // the split and pays the default recipient again.
+    pub fn set_royalty_splits(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        shares: Vec<RoyaltyShare>,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::RoyaltyAdmin)?;
+
+        if shares.is_empty() {
+            <DataKey as Storage>::remove_royalty_splits(env, collection_id);
+            events::royalty_splits_updated(env, collection_id, 0);
+            return Ok(());
+        }
+
+        if shares.len() > MAX_ROYALTY_RECIPIENTS {
+            return Err(Error::InvalidRoyaltySplit);
+        }
+        for (index, share) in shares.iter().enumerate() {
+            let duplicate = shares
+                .iter()
+                .skip(index + 1)
+                .any(|other| other.recipient == share.recipient);
+            if share.share == 0 || duplicate {
+                return Err(Error::InvalidRoyaltySplit);
+            }
+        }
+
+        let percentage = match <DataKey as Storage>::get_royalty_info(env, collection_id) {
+            Some(royalty) => royalty.percentage,
+            None => {
+                let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+                info.config.royalty_percentage
+            }
+        };
+        if Self::split_total(&shares) != percentage {
+            return Err(Error::RoyaltySplitMismatch);
+        }
+
+        <DataKey as Storage>::set_royalty_splits(env, collection_id, &shares);
+
+        events::royalty_splits_updated(env, collection_id, shares.len());
+        Ok(())
+    }
+
+    fn split_total(shares: &Vec<RoyaltyShare>) -> u32 {
+        shares
+            .iter()
+            .fold(0u32, |total, share| total.saturating_add(share.share))
+    }
+
+    // Overrides the collection royalty for one token, or clears the override
+    // when royalty is None
+    pub fn set_token_royalty_info(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        token_id: u32,
+        royalty: Option<RoyaltyInfo>,
+    ) -> Result<(), Error> {
+        Self::require_role(env, collection_id, caller, Role::RoyaltyAdmin)?;
+
+        if <DataKey as Storage>::get_token_owner(env, collection_id, token_id).is_none() {
+            return Err(Error::TokenNotFound);
+        }
+
+        match &royalty {
+            Some(royalty) => {
+                if royalty.percentage > MAX_ROYALTY_BPS {
+                    return Err(Error::InvalidRoyaltyPercentage);
+                }
+                <DataKey as Storage>::set_token_royalty_info(env, collection_id, token_id, royalty);
+            }
+            None => <DataKey as Storage>::remove_token_royalty_info(env, collection_id, token_id),
+        }
+
+        events::token_royalty_updated(env, collection_id, token_id, &royalty);
+        Ok(())
+    }
+
+    // Config changes after creation. Supply can only shrink, down to the
+    // tokens in circulation, and pausability can only be given up.
+    pub fn update_collection_config(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        update: CollectionConfigUpdate,
+    ) -> Result<(), Error> {
+        let mut info = Self::require_admin(env, collection_id, caller)?;
+        let old_config = info.config.clone();
+
+        if let Some(description) = update.description {
+            info.config.description = description;
+        }
+
+        if let Some(base_uri) = update.base_uri {
+            if Self::is_metadata_frozen(env, collection_id, None) {
+                return Err(Error::MetadataFrozen);
+            }
+            info.config.base_uri = base_uri;
+        }
+
+        if let Some(max_supply) = update.max_supply {
+            let increases = old_config
+                .max_supply
+                .is_some_and(|current| max_supply > current);
+            if increases || max_supply < info.total_tokens {
+                return Err(Error::InvalidConfig);
+            }
+            info.config.max_supply = Some(max_supply);
+        }
+
+        if let Some(is_public_mint) = update.is_public_mint {
+            info.config.is_public_mint = is_public_mint;
+        }
+
+        if let Some(is_pausable) = update.is_pausable {
+            // A paused collection that stops being pausable could never resume
+            if is_pausable && !old_config.is_pausable {
+                return Err(Error::InvalidConfig);
+            }
+            if !is_pausable && info.is_paused {
+                return Err(Error::InvalidConfig);
+            }
+            info.config.is_pausable = is_pausable;
+        }
+
+        Factory::validate_collection_config(&info.config)?;
+        <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+
+        events::collection_config_updated(env, collection_id, &old_config, &info.config);
+        Ok(())
+    }
+
+    // Upgrades. A deployed collection contract calls upgrade_collection on
+    // behalf of its admin before replacing its own Wasm.
+    pub fn upgrade_collection(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        // Legacy collections live inside the factory and have no contract of
+        // their own to upgrade
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        if !info.config.is_upgradeable || info.address == env.current_contract_address() {
+            return Err(Error::NotUpgradeable);
+        }
+
+        Self::require_admin(env, collection_id, caller)?;
+        info.address.require_auth();
+
+        let old_wasm_hash = <DataKey as Storage>::get_collection_contract_wasm(env, collection_id);
+        <DataKey as Storage>::set_collection_contract_wasm(env, collection_id, &new_wasm_hash);
+
+        events::collection_upgraded(env, collection_id, &old_wasm_hash, &new_wasm_hash);
+        Ok(())
+    }
+
+    // Permanently turns off upgrades for the collection contract
+    pub fn renounce_upgradeability(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+    ) -> Result<(), Error> {
+        let mut info = Self::require_admin(env, collection_id, caller)?;
+
+        if !info.config.is_upgradeable {
+            return Err(Error::NotUpgradeable);
+        }
+
+        info.config.is_upgradeable = false;
+        <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+
+        events::upgradeability_renounced(env, collection_id);
         Ok(())
     }
 
+    pub fn collection_contract_wasm(env: &Env, collection_id: u64) -> Option<BytesN<32>> {
+        <DataKey as Storage>::get_collection_contract_wasm(env, collection_id)
+    }
+
     // Pause
     pub fn set_paused(
         env: &Env,
@@ -290,14 +1545,316 @@ impl Collection {
         caller: &Address,
         paused: bool,
     ) -> Result<(), Error> {
-        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        let mut info = Self::require_role(env, collection_id, caller, Role::Pauser)?;
 
-        if !info.config.is_pausable || &info.creator != caller {
+        if !info.config.is_pausable {
             return Err(Error::Unauthorized);
         }
 
         <DataKey as Storage>::set_collection_paused(env, collection_id, paused);
 
+        info.is_paused = paused;
+        <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+
+        events::pause_updated(env, collection_id, paused);
+        Ok(())
+    }
+
+    // Roles
+    pub fn grant_role(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        role: Role,
+        account: &Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(env, collection_id, caller)?;
+
+        if <DataKey as Storage>::get_role_member_index(env, collection_id, role, account).is_none()
+        {
+            Self::add_role_member(env, collection_id, role, account);
+            events::role_granted(env, collection_id, role, account, caller);
+        }
+
+        Ok(())
+    }
+
+    pub fn revoke_role(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        role: Role,
+        account: &Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(env, collection_id, caller)?;
+
+        if let Some(index) =
+            <DataKey as Storage>::get_role_member_index(env, collection_id, role, account)
+        {
+            Self::remove_role_member(env, collection_id, role, account, index);
+            events::role_revoked(env, collection_id, role, account, caller);
+        }
+
+        Ok(())
+    }
+
+    pub fn has_role(env: &Env, collection_id: u64, role: Role, account: &Address) -> bool {
+        <DataKey as Storage>::get_role_member_index(env, collection_id, role, account).is_some()
+    }
+
+    pub fn role_members(
+        env: &Env,
+        collection_id: u64,
+        role: Role,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        let count = <DataKey as Storage>::get_role_member_count(env, collection_id, role);
+        let end = count.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut members = Vec::new(env);
+        for index in start..end {
+            if let Some(account) =
+                <DataKey as Storage>::get_role_member(env, collection_id, role, index)
+            {
+                members.push_back(account);
+            }
+        }
+        members
+    }
+
+    // Admin handover
+    pub fn propose_collection_admin(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        Self::require_admin(env, collection_id, caller)?;
+
+        if expiration_ledger <= env.ledger().sequence() {
+            return Err(Error::InvalidInput);
+        }
+
+        let pending = PendingTransfer {
+            new_admin: new_admin.clone(),
+            expiration_ledger,
+        };
+        <DataKey as Storage>::set_pending_collection_admin(env, collection_id, &pending);
+
+        events::collection_admin_proposed(
+            env,
+            collection_id,
+            caller,
+            &new_admin,
+            expiration_ledger,
+        );
+        Ok(())
+    }
+
+    pub fn accept_collection_admin(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let pending = <DataKey as Storage>::get_pending_collection_admin(env, collection_id)
+            .ok_or(Error::NoPendingTransfer)?;
+        if &pending.new_admin != caller {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(Error::TransferOfferExpired);
+        }
+
+        let mut info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        let old_admin = info.admin;
+        info.admin = caller.clone();
+        <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+        <DataKey as Storage>::remove_pending_collection_admin(env, collection_id);
+
+        events::collection_admin_transferred(env, collection_id, &old_admin, caller);
+        Ok(())
+    }
+
+    pub fn cancel_collection_admin_proposal(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+    ) -> Result<(), Error> {
+        Self::require_admin(env, collection_id, caller)?;
+
+        let pending = <DataKey as Storage>::get_pending_collection_admin(env, collection_id)
+            .ok_or(Error::NoPendingTransfer)?;
+        <DataKey as Storage>::remove_pending_collection_admin(env, collection_id);
+
+        events::collection_admin_cancelled(env, collection_id, &pending.new_admin);
         Ok(())
     }
+
+    pub fn get_pending_collection_admin(env: &Env, collection_id: u64) -> Option<PendingTransfer> {
+        <DataKey as Storage>::get_pending_collection_admin(env, collection_id)
+    }
+
+    // Each member has its own entry, plus a slot in the role's list so that
+    // role_members can page through them
+    fn add_role_member(env: &Env, collection_id: u64, role: Role, account: &Address) {
+        let index = <DataKey as Storage>::get_role_member_count(env, collection_id, role);
+        <DataKey as Storage>::set_role_member(env, collection_id, role, index, account);
+        <DataKey as Storage>::set_role_member_index(env, collection_id, role, account, index);
+        <DataKey as Storage>::set_role_member_count(env, collection_id, role, index + 1);
+    }
+
+    // Frees the member's slot by moving the last member into it
+    fn remove_role_member(
+        env: &Env,
+        collection_id: u64,
+        role: Role,
+        account: &Address,
+        index: u32,
+    ) {
+        let last_index = <DataKey as Storage>::get_role_member_count(env, collection_id, role) - 1;
+
+        if index != last_index {
+            if let Some(last_member) =
+                <DataKey as Storage>::get_role_member(env, collection_id, role, last_index)
+            {
+                <DataKey as Storage>::set_role_member(
+                    env,
+                    collection_id,
+                    role,
+                    index,
+                    &last_member,
+                );
+                <DataKey as Storage>::set_role_member_index(
+                    env,
+                    collection_id,
+                    role,
+                    &last_member,
+                    index,
+                );
+            }
+        }
+
+        <DataKey as Storage>::remove_role_member(env, collection_id, role, last_index);
+        <DataKey as Storage>::remove_role_member_index(env, collection_id, role, account);
+        <DataKey as Storage>::set_role_member_count(env, collection_id, role, last_index);
+    }
+
+    // Appends token_id to the owner's list; must run before the balance is
+    // incremented since the balance is the list length
+    fn add_token_to_owner(env: &Env, collection_id: u64, owner: &Address, token_id: u32) {
+        let index = <DataKey as Storage>::get_balance(env, collection_id, owner);
+        <DataKey as Storage>::set_owner_token(env, collection_id, owner, index, token_id);
+        <DataKey as Storage>::set_owner_token_index(env, collection_id, token_id, index);
+    }
+
+    // Removes token_id from the owner's list by moving the last token into its
+    // slot; must run before the balance is decremented
+    fn remove_token_from_owner(env: &Env, collection_id: u64, owner: &Address, token_id: u32) {
+        let last_index = <DataKey as Storage>::get_balance(env, collection_id, owner) - 1;
+        let index = <DataKey as Storage>::get_owner_token_index(env, collection_id, token_id)
+            .unwrap_or(last_index);
+
+        if index != last_index {
+            if let Some(last_token_id) =
+                <DataKey as Storage>::get_owner_token(env, collection_id, owner, last_index)
+            {
+                <DataKey as Storage>::set_owner_token(
+                    env,
+                    collection_id,
+                    owner,
+                    index,
+                    last_token_id,
+                );
+                <DataKey as Storage>::set_owner_token_index(
+                    env,
+                    collection_id,
+                    last_token_id,
+                    index,
+                );
+            }
+        }
+
+        <DataKey as Storage>::remove_owner_token(env, collection_id, owner, last_index);
+        <DataKey as Storage>::remove_owner_token_index(env, collection_id, token_id);
+    }
+
+    // Appends token_id to the collection's list at index total_tokens
+    fn add_token_to_all(env: &Env, collection_id: u64, total_tokens: u32, token_id: u32) {
+        <DataKey as Storage>::set_token_by_index(env, collection_id, total_tokens, token_id);
+        <DataKey as Storage>::set_token_index(env, collection_id, token_id, total_tokens);
+    }
+
+    // Removes token_id from the collection's list, moving the last token into
+    // its slot
+    fn remove_token_from_all(env: &Env, collection_id: u64, total_tokens: u32, token_id: u32) {
+        let last_index = total_tokens - 1;
+        let index = <DataKey as Storage>::get_token_index(env, collection_id, token_id)
+            .unwrap_or(last_index);
+
+        if index != last_index {
+            if let Some(last_token_id) =
+                <DataKey as Storage>::get_token_by_index(env, collection_id, last_index)
+            {
+                <DataKey as Storage>::set_token_by_index(env, collection_id, index, last_token_id);
+                <DataKey as Storage>::set_token_index(env, collection_id, last_token_id, index);
+            }
+        }
+
+        <DataKey as Storage>::remove_token_by_index(env, collection_id, last_index);
+        <DataKey as Storage>::remove_token_index(env, collection_id, token_id);
+    }
+
+    // Loads the collection after checking that caller is authorized and is
+    // either its admin or a holder of `role`
+    fn require_role(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+        role: Role,
+    ) -> Result<CollectionInfo, Error> {
+        caller.require_auth();
+
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        if &info.admin != caller && !Self::has_role(env, collection_id, role, caller) {
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(info)
+    }
+
+    // Loads the collection after checking that caller is its authorized admin
+    fn require_admin(
+        env: &Env,
+        collection_id: u64,
+        caller: &Address,
+    ) -> Result<CollectionInfo, Error> {
+        caller.require_auth();
+
+        let info = <DataKey as Storage>::get_collection_info(env, collection_id)?;
+        if &info.admin != caller {
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(info)
+    }
+}
+
+// Formats a token id as decimal ASCII for derived URIs
+fn decimal_bytes(env: &Env, value: u32) -> Bytes {
+    let mut buf = [0u8; 10];
+    let mut start = buf.len();
+    let mut remaining = value;
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (remaining % 10) as u8;
+        remaining /= 10;
+        if remaining == 0 {
+            break;
+        }
+    }
+    Bytes::from_slice(env, &buf[start..])
 }
@@ -13,6 +13,10 @@ pub enum Error {
     CollectionNotFound = 1004,
     CollectionAlreadyExists = 1005,
     AlreadyInitialized = 1006, // ADD THIS
+    CollectionWasmNotSet = 1007,
+    FeeTokenNotSet = 1008,
+    NoPendingTransfer = 1009,
+    TransferOfferExpired = 1010,
 
     // Collection errors (2000-2999)
     MaxSupplyExceeded = 2000,
@@ -24,6 +28,24 @@ pub enum Error {
     InvalidTokenId = 2006,
     InvalidRoyaltyPercentage = 2007,
     NotApproved = 2008, // ADD THIS - used in collection.rs
+    InvalidProof = 2009,
+    AllowanceExceeded = 2010,
+    NoActivePhase = 2011,
+    WalletLimitReached = 2012,
+    MetadataFrozen = 2013,
+    InvalidMetadata = 2014,
+    MintStarted = 2015,
+    AlreadyRevealed = 2016,
+    InvalidRoyaltySplit = 2017,
+    RoyaltySplitMismatch = 2018,
+    TransferRestricted = 2019,
+    NotUpgradeable = 2020,
+    InvalidLiveUntilLedger = 2021,
+    PermitExpired = 2022,
+    InvalidNonce = 2023,
+    VoucherExpired = 2024,
+    VoucherAlreadyRedeemed = 2025,
+    RoyaltySplitsActive = 2026,
 
     // General errors (3000-3999)
     InvalidInput = 3000,
@@ -31,6 +53,7 @@ pub enum Error {
     Underflow = 3002,
     StorageError = 3003,
     TransferFailed = 3004,
+    MigrationRequired = 3005,
 }
 
 impl Error {
@@ -43,6 +66,10 @@ impl Error {
             Error::CollectionNotFound => Symbol::new(env, "COLLECTION_NOT_FOUND"),
             Error::CollectionAlreadyExists => Symbol::new(env, "COLLECTION_ALREADY_EXISTS"),
             Error::AlreadyInitialized => Symbol::new(env, "ALREADY_INITIALIZED"), // ADD THIS
+            Error::CollectionWasmNotSet => Symbol::new(env, "COLLECTION_WASM_NOT_SET"),
+            Error::FeeTokenNotSet => Symbol::new(env, "FEE_TOKEN_NOT_SET"),
+            Error::NoPendingTransfer => Symbol::new(env, "NO_PENDING_TRANSFER"),
+            Error::TransferOfferExpired => Symbol::new(env, "TRANSFER_OFFER_EXPIRED"),
             Error::MaxSupplyExceeded => Symbol::new(env, "MAX_SUPPLY_EXCEEDED"),
             Error::TokenNotFound => Symbol::new(env, "TOKEN_NOT_FOUND"),
             Error::NotTokenOwner => Symbol::new(env, "NOT_TOKEN_OWNER"),
@@ -52,11 +79,30 @@ impl Error {
             Error::WhitelistRequired => Symbol::new(env, "WHITELIST_REQUIRED"),
             Error::InvalidTokenId => Symbol::new(env, "INVALID_TOKEN_ID"),
             Error::InvalidRoyaltyPercentage => Symbol::new(env, "INVALID_ROYALTY_PERCENTAGE"),
+            Error::InvalidProof => Symbol::new(env, "INVALID_PROOF"),
+            Error::AllowanceExceeded => Symbol::new(env, "ALLOWANCE_EXCEEDED"),
+            Error::NoActivePhase => Symbol::new(env, "NO_ACTIVE_PHASE"),
+            Error::WalletLimitReached => Symbol::new(env, "WALLET_LIMIT_REACHED"),
+            Error::MetadataFrozen => Symbol::new(env, "METADATA_FROZEN"),
+            Error::InvalidMetadata => Symbol::new(env, "INVALID_METADATA"),
+            Error::MintStarted => Symbol::new(env, "MINT_STARTED"),
+            Error::AlreadyRevealed => Symbol::new(env, "ALREADY_REVEALED"),
+            Error::InvalidRoyaltySplit => Symbol::new(env, "INVALID_ROYALTY_SPLIT"),
+            Error::RoyaltySplitMismatch => Symbol::new(env, "ROYALTY_SPLIT_MISMATCH"),
+            Error::TransferRestricted => Symbol::new(env, "TRANSFER_RESTRICTED"),
+            Error::NotUpgradeable => Symbol::new(env, "NOT_UPGRADEABLE"),
+            Error::InvalidLiveUntilLedger => Symbol::new(env, "INVALID_LIVE_UNTIL_LEDGER"),
+            Error::PermitExpired => Symbol::new(env, "PERMIT_EXPIRED"),
+            Error::InvalidNonce => Symbol::new(env, "INVALID_NONCE"),
+            Error::VoucherExpired => Symbol::new(env, "VOUCHER_EXPIRED"),
+            Error::VoucherAlreadyRedeemed => Symbol::new(env, "VOUCHER_ALREADY_REDEEMED"),
+            Error::RoyaltySplitsActive => Symbol::new(env, "ROYALTY_SPLITS_ACTIVE"),
             Error::InvalidInput => Symbol::new(env, "INVALID_INPUT"),
             Error::Overflow => Symbol::new(env, "OVERFLOW"),
             Error::Underflow => Symbol::new(env, "UNDERFLOW"),
             Error::StorageError => Symbol::new(env, "STORAGE_ERROR"),
             Error::TransferFailed => Symbol::new(env, "TRANSFER_FAILED"),
+            Error::MigrationRequired => Symbol::new(env, "MIGRATION_REQUIRED"),
         }
     }
 }
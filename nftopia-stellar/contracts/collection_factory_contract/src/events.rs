@@ -0,0 +1,940 @@
+use soroban_sdk::{contractevent, Address, BytesN, Env, String};
+
+use crate::storage::{CollectionConfig, MintPrice, Role, RoyaltyInfo};
+
+// Bumped whenever the topics or data of an existing event change, so that
+// indexers can decode events emitted by older contract versions.
+pub const EVENT_VERSION: u32 = 2;
+
+// Every event is published with the event name as its first topic. Collection
+// events add the collection_id as a second topic; all other fields are data.
+
+// ─────────────────────────────────────────────
+// Collection events
+// ─────────────────────────────────────────────
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mint {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transfer {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub from: Address,
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Burn {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub from: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Approve {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub owner: Address,
+    pub approved: Address,
+    pub token_id: u32,
+    // 0 when the approval was cleared
+    pub live_until_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApproveForAll {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub owner: Address,
+    pub operator: Address,
+    // 0 when the operator was revoked
+    pub live_until_ledger: u32,
+}
+
+// token_id is None when the change affects every token, e.g. a new base URI
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub token_id: Option<u32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataFrozen {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub token_id: Option<u32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub recipient: Address,
+    pub percentage: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltySplitsUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub recipient_count: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenRoyaltyUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub token_id: u32,
+    pub royalty: Option<RoyaltyInfo>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WhitelistUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub address: Address,
+    pub whitelisted: bool,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintPaid {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub payer: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub platform_share: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintPriceUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub price: Option<MintPrice>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintPhasesUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub phase_count: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttributeSchemaUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub attribute_count: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenUriConfigured {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub provenance_hash: Option<BytesN<32>>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Revealed {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub base_uri: String,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleRootUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub phase: u32,
+    pub root: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub paused: bool,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionAdminProposed {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub admin: Address,
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionAdminTransferred {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub old_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionAdminCancelled {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleGranted {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleRevoked {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub role: Role,
+    pub account: Address,
+    pub sender: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionConfigUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub old_config: CollectionConfig,
+    pub new_config: CollectionConfig,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionUpgraded {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub old_wasm_hash: Option<BytesN<32>>,
+    pub new_wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeabilityRenounced {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinterKeyUpdated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub public_key: BytesN<32>,
+    pub authorized: bool,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherRedeemed {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub voucher_id: BytesN<32>,
+    pub buyer: Address,
+    pub token_id: u32,
+}
+
+// ─────────────────────────────────────────────
+// Factory events
+// ─────────────────────────────────────────────
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionCreated {
+    #[topic]
+    pub collection_id: u64,
+    pub version: u32,
+    pub creator: Address,
+    pub address: Address,
+    pub fee: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryInitialized {
+    pub version: u32,
+    pub owner: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionWasmUpdated {
+    pub version: u32,
+    pub wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeUpdated {
+    pub version: u32,
+    pub old_fee: i128,
+    pub new_fee: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlatformFeeUpdated {
+    pub version: u32,
+    pub old_bps: u32,
+    pub new_bps: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTokenUpdated {
+    pub version: u32,
+    pub fee_token: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeesWithdrawn {
+    pub version: u32,
+    pub recipient: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaxCollectionsUpdated {
+    pub version: u32,
+    pub max_collections: Option<u32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryActiveUpdated {
+    pub version: u32,
+    pub active: bool,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerProposed {
+    pub version: u32,
+    pub owner: Address,
+    pub new_owner: Address,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerTransferred {
+    pub version: u32,
+    pub old_owner: Address,
+    pub new_owner: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OwnerProposalCancelled {
+    pub version: u32,
+    pub new_owner: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryUpgraded {
+    pub version: u32,
+    pub old_wasm_hash: Option<BytesN<32>>,
+    pub new_wasm_hash: BytesN<32>,
+}
+
+// An owner voided every approval they had granted, across all collections
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ApprovalsRevoked {
+    pub version: u32,
+    pub owner: Address,
+}
+
+// public_key is None when the owner removed their permit key
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermitKeyUpdated {
+    pub version: u32,
+    pub owner: Address,
+    pub public_key: Option<BytesN<32>>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaMigrated {
+    pub version: u32,
+    pub from_schema: u32,
+    pub to_schema: u32,
+}
+
+// ─────────────────────────────────────────────
+// Publishing
+// ─────────────────────────────────────────────
+pub fn mint(env: &Env, collection_id: u64, to: &Address, token_id: u32) {
+    Mint {
+        collection_id,
+        version: EVENT_VERSION,
+        to: to.clone(),
+        token_id,
+    }
+    .publish(env);
+}
+
+pub fn transfer(env: &Env, collection_id: u64, from: &Address, to: &Address, token_id: u32) {
+    Transfer {
+        collection_id,
+        version: EVENT_VERSION,
+        from: from.clone(),
+        to: to.clone(),
+        token_id,
+    }
+    .publish(env);
+}
+
+pub fn burn(env: &Env, collection_id: u64, from: &Address, token_id: u32) {
+    Burn {
+        collection_id,
+        version: EVENT_VERSION,
+        from: from.clone(),
+        token_id,
+    }
+    .publish(env);
+}
+
+pub fn approve(
+    env: &Env,
+    collection_id: u64,
+    owner: &Address,
+    approved: &Address,
+    token_id: u32,
+    live_until_ledger: u32,
+) {
+    Approve {
+        collection_id,
+        version: EVENT_VERSION,
+        owner: owner.clone(),
+        approved: approved.clone(),
+        token_id,
+        live_until_ledger,
+    }
+    .publish(env);
+}
+
+pub fn approve_for_all(
+    env: &Env,
+    collection_id: u64,
+    owner: &Address,
+    operator: &Address,
+    live_until_ledger: u32,
+) {
+    ApproveForAll {
+        collection_id,
+        version: EVENT_VERSION,
+        owner: owner.clone(),
+        operator: operator.clone(),
+        live_until_ledger,
+    }
+    .publish(env);
+}
+
+pub fn metadata_updated(env: &Env, collection_id: u64, token_id: Option<u32>) {
+    MetadataUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        token_id,
+    }
+    .publish(env);
+}
+
+pub fn metadata_frozen(env: &Env, collection_id: u64, token_id: Option<u32>) {
+    MetadataFrozen {
+        collection_id,
+        version: EVENT_VERSION,
+        token_id,
+    }
+    .publish(env);
+}
+
+pub fn royalty_updated(env: &Env, collection_id: u64, recipient: &Address, percentage: u32) {
+    RoyaltyUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        recipient: recipient.clone(),
+        percentage,
+    }
+    .publish(env);
+}
+
+pub fn royalty_splits_updated(env: &Env, collection_id: u64, recipient_count: u32) {
+    RoyaltySplitsUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        recipient_count,
+    }
+    .publish(env);
+}
+
+pub fn token_royalty_updated(
+    env: &Env,
+    collection_id: u64,
+    token_id: u32,
+    royalty: &Option<RoyaltyInfo>,
+) {
+    TokenRoyaltyUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        token_id,
+        royalty: royalty.clone(),
+    }
+    .publish(env);
+}
+
+pub fn whitelist_updated(env: &Env, collection_id: u64, address: &Address, whitelisted: bool) {
+    WhitelistUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        address: address.clone(),
+        whitelisted,
+    }
+    .publish(env);
+}
+
+pub fn mint_paid(
+    env: &Env,
+    collection_id: u64,
+    payer: &Address,
+    token: &Address,
+    amount: i128,
+    platform_share: i128,
+) {
+    MintPaid {
+        collection_id,
+        version: EVENT_VERSION,
+        payer: payer.clone(),
+        token: token.clone(),
+        amount,
+        platform_share,
+    }
+    .publish(env);
+}
+
+pub fn minter_key_updated(
+    env: &Env,
+    collection_id: u64,
+    public_key: &BytesN<32>,
+    authorized: bool,
+) {
+    MinterKeyUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        public_key: public_key.clone(),
+        authorized,
+    }
+    .publish(env);
+}
+
+pub fn voucher_redeemed(
+    env: &Env,
+    collection_id: u64,
+    voucher_id: &BytesN<32>,
+    buyer: &Address,
+    token_id: u32,
+) {
+    VoucherRedeemed {
+        collection_id,
+        version: EVENT_VERSION,
+        voucher_id: voucher_id.clone(),
+        buyer: buyer.clone(),
+        token_id,
+    }
+    .publish(env);
+}
+
+pub fn mint_price_updated(env: &Env, collection_id: u64, price: Option<MintPrice>) {
+    MintPriceUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        price,
+    }
+    .publish(env);
+}
+
+pub fn mint_phases_updated(env: &Env, collection_id: u64, phase_count: u32) {
+    MintPhasesUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        phase_count,
+    }
+    .publish(env);
+}
+
+pub fn attribute_schema_updated(env: &Env, collection_id: u64, attribute_count: u32) {
+    AttributeSchemaUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        attribute_count,
+    }
+    .publish(env);
+}
+
+pub fn token_uri_configured(env: &Env, collection_id: u64, provenance_hash: &Option<BytesN<32>>) {
+    TokenUriConfigured {
+        collection_id,
+        version: EVENT_VERSION,
+        provenance_hash: provenance_hash.clone(),
+    }
+    .publish(env);
+}
+
+pub fn revealed(env: &Env, collection_id: u64, base_uri: &String) {
+    Revealed {
+        collection_id,
+        version: EVENT_VERSION,
+        base_uri: base_uri.clone(),
+    }
+    .publish(env);
+}
+
+pub fn merkle_root_updated(env: &Env, collection_id: u64, phase: u32, root: &BytesN<32>) {
+    MerkleRootUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        phase,
+        root: root.clone(),
+    }
+    .publish(env);
+}
+
+pub fn pause_updated(env: &Env, collection_id: u64, paused: bool) {
+    PauseUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        paused,
+    }
+    .publish(env);
+}
+
+pub fn collection_admin_proposed(
+    env: &Env,
+    collection_id: u64,
+    admin: &Address,
+    new_admin: &Address,
+    expiration_ledger: u32,
+) {
+    CollectionAdminProposed {
+        collection_id,
+        version: EVENT_VERSION,
+        admin: admin.clone(),
+        new_admin: new_admin.clone(),
+        expiration_ledger,
+    }
+    .publish(env);
+}
+
+pub fn collection_admin_transferred(
+    env: &Env,
+    collection_id: u64,
+    old_admin: &Address,
+    new_admin: &Address,
+) {
+    CollectionAdminTransferred {
+        collection_id,
+        version: EVENT_VERSION,
+        old_admin: old_admin.clone(),
+        new_admin: new_admin.clone(),
+    }
+    .publish(env);
+}
+
+pub fn collection_admin_cancelled(env: &Env, collection_id: u64, new_admin: &Address) {
+    CollectionAdminCancelled {
+        collection_id,
+        version: EVENT_VERSION,
+        new_admin: new_admin.clone(),
+    }
+    .publish(env);
+}
+
+pub fn role_granted(
+    env: &Env,
+    collection_id: u64,
+    role: Role,
+    account: &Address,
+    sender: &Address,
+) {
+    RoleGranted {
+        collection_id,
+        version: EVENT_VERSION,
+        role,
+        account: account.clone(),
+        sender: sender.clone(),
+    }
+    .publish(env);
+}
+
+pub fn role_revoked(
+    env: &Env,
+    collection_id: u64,
+    role: Role,
+    account: &Address,
+    sender: &Address,
+) {
+    RoleRevoked {
+        collection_id,
+        version: EVENT_VERSION,
+        role,
+        account: account.clone(),
+        sender: sender.clone(),
+    }
+    .publish(env);
+}
+
+pub fn collection_config_updated(
+    env: &Env,
+    collection_id: u64,
+    old_config: &CollectionConfig,
+    new_config: &CollectionConfig,
+) {
+    CollectionConfigUpdated {
+        collection_id,
+        version: EVENT_VERSION,
+        old_config: old_config.clone(),
+        new_config: new_config.clone(),
+    }
+    .publish(env);
+}
+
+pub fn collection_upgraded(
+    env: &Env,
+    collection_id: u64,
+    old_wasm_hash: &Option<BytesN<32>>,
+    new_wasm_hash: &BytesN<32>,
+) {
+    CollectionUpgraded {
+        collection_id,
+        version: EVENT_VERSION,
+        old_wasm_hash: old_wasm_hash.clone(),
+        new_wasm_hash: new_wasm_hash.clone(),
+    }
+    .publish(env);
+}
+
+pub fn upgradeability_renounced(env: &Env, collection_id: u64) {
+    UpgradeabilityRenounced {
+        collection_id,
+        version: EVENT_VERSION,
+    }
+    .publish(env);
+}
+
+pub fn collection_created(
+    env: &Env,
+    collection_id: u64,
+    creator: &Address,
+    address: &Address,
+    fee: i128,
+) {
+    CollectionCreated {
+        collection_id,
+        version: EVENT_VERSION,
+        creator: creator.clone(),
+        address: address.clone(),
+        fee,
+    }
+    .publish(env);
+}
+
+pub fn factory_initialized(env: &Env, owner: &Address) {
+    FactoryInitialized {
+        version: EVENT_VERSION,
+        owner: owner.clone(),
+    }
+    .publish(env);
+}
+
+pub fn collection_wasm_updated(env: &Env, wasm_hash: &BytesN<32>) {
+    CollectionWasmUpdated {
+        version: EVENT_VERSION,
+        wasm_hash: wasm_hash.clone(),
+    }
+    .publish(env);
+}
+
+pub fn fee_updated(env: &Env, old_fee: i128, new_fee: i128) {
+    FeeUpdated {
+        version: EVENT_VERSION,
+        old_fee,
+        new_fee,
+    }
+    .publish(env);
+}
+
+pub fn platform_fee_updated(env: &Env, old_bps: u32, new_bps: u32) {
+    PlatformFeeUpdated {
+        version: EVENT_VERSION,
+        old_bps,
+        new_bps,
+    }
+    .publish(env);
+}
+
+pub fn fee_token_updated(env: &Env, fee_token: &Address) {
+    FeeTokenUpdated {
+        version: EVENT_VERSION,
+        fee_token: fee_token.clone(),
+    }
+    .publish(env);
+}
+
+pub fn fees_withdrawn(env: &Env, recipient: &Address, amount: i128) {
+    FeesWithdrawn {
+        version: EVENT_VERSION,
+        recipient: recipient.clone(),
+        amount,
+    }
+    .publish(env);
+}
+
+pub fn max_collections_updated(env: &Env, max_collections: Option<u32>) {
+    MaxCollectionsUpdated {
+        version: EVENT_VERSION,
+        max_collections,
+    }
+    .publish(env);
+}
+
+pub fn factory_active_updated(env: &Env, active: bool) {
+    FactoryActiveUpdated {
+        version: EVENT_VERSION,
+        active,
+    }
+    .publish(env);
+}
+
+pub fn owner_proposed(env: &Env, owner: &Address, new_owner: &Address, expiration_ledger: u32) {
+    OwnerProposed {
+        version: EVENT_VERSION,
+        owner: owner.clone(),
+        new_owner: new_owner.clone(),
+        expiration_ledger,
+    }
+    .publish(env);
+}
+
+pub fn owner_transferred(env: &Env, old_owner: &Address, new_owner: &Address) {
+    OwnerTransferred {
+        version: EVENT_VERSION,
+        old_owner: old_owner.clone(),
+        new_owner: new_owner.clone(),
+    }
+    .publish(env);
+}
+
+pub fn owner_proposal_cancelled(env: &Env, new_owner: &Address) {
+    OwnerProposalCancelled {
+        version: EVENT_VERSION,
+        new_owner: new_owner.clone(),
+    }
+    .publish(env);
+}
+
+pub fn factory_upgraded(env: &Env, old_wasm_hash: &Option<BytesN<32>>, new_wasm_hash: &BytesN<32>) {
+    FactoryUpgraded {
+        version: EVENT_VERSION,
+        old_wasm_hash: old_wasm_hash.clone(),
+        new_wasm_hash: new_wasm_hash.clone(),
+    }
+    .publish(env);
+}
+
+pub fn schema_migrated(env: &Env, from_schema: u32, to_schema: u32) {
+    SchemaMigrated {
+        version: EVENT_VERSION,
+        from_schema,
+        to_schema,
+    }
+    .publish(env);
+}
+
+pub fn approvals_revoked(env: &Env, owner: &Address) {
+    ApprovalsRevoked {
+        version: EVENT_VERSION,
+        owner: owner.clone(),
+    }
+    .publish(env);
+}
+
+pub fn permit_key_updated(env: &Env, owner: &Address, public_key: &Option<BytesN<32>>) {
+    PermitKeyUpdated {
+        version: EVENT_VERSION,
+        owner: owner.clone(),
+        public_key: public_key.clone(),
+    }
+    .publish(env);
+}
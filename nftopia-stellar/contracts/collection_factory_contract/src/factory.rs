@@ -1,8 +1,13 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{token, Address, BytesN, Env, Vec};
 
 use crate::{
     errors::Error,
-    storage::{CollectionConfig, CollectionInfo, DataKey, FactoryConfig, Storage},
+    events,
+    migration::{self, LegacyKey},
+    storage::{
+        CollectionConfig, CollectionInfo, CollectionStatus, DataKey, FactoryConfig,
+        PendingTransfer, Storage, BPS_DENOMINATOR, MAX_PAGE_SIZE, SCHEMA_VERSION,
+    },
 };
 
 pub struct Factory;
@@ -12,13 +17,17 @@ impl Factory {
     // Initialize factory
     // ─────────────────────────────────────────────
     pub fn initialize(env: &Env, owner: Address) -> Result<(), Error> {
-        if <DataKey as Storage>::get_factory_config(env).is_ok() {
+        owner.require_auth();
+
+        if <DataKey as Storage>::has_factory_config(env) {
             return Err(Error::AlreadyInitialized);
         }
 
         let config = FactoryConfig {
-            owner,
+            owner: owner.clone(),
             factory_fee: 0,
+            fee_token: None,
+            platform_fee_bps: 0,
             max_collections: None,
             total_collections: 0,
             accumulated_fees: 0,
@@ -26,6 +35,9 @@ impl Factory {
         };
 
         <DataKey as Storage>::set_factory_config(env, &config);
+        <DataKey as Storage>::set_schema_version(env, SCHEMA_VERSION);
+
+        events::factory_initialized(env, &owner);
         Ok(())
     }
 
@@ -38,6 +50,8 @@ impl Factory {
         config: CollectionConfig,
         initial_royalty_recipient: Option<Address>,
     ) -> Result<u64, Error> {
+        caller.require_auth();
+
         let mut factory_config = <DataKey as Storage>::get_factory_config(env)?;
 
         if !factory_config.is_active {
@@ -52,14 +66,31 @@ impl Factory {
 
         Self::validate_collection_config(&config)?;
 
+        let wasm_hash = <DataKey as Storage>::get_collection_wasm_hash(env)
+            .ok_or(Error::CollectionWasmNotSet)?;
+
+        Self::charge_creation_fee(env, caller, &factory_config)?;
+
         let collection_id = factory_config.total_collections as u64 + 1;
 
-        // All collections share the same contract address but different collection_id
-        let collection_address = env.current_contract_address();
+        // Each collection gets its own contract, deployed at an address derived
+        // from the factory and collection_id
+        let collection_address = env
+            .deployer()
+            .with_current_contract(Self::collection_salt(env, collection_id))
+            .deploy_v2(
+                wasm_hash.clone(),
+                (
+                    env.current_contract_address(),
+                    collection_id,
+                    config.clone(),
+                ),
+            );
 
         let info = CollectionInfo {
             address: collection_address.clone(),
             creator: caller.clone(),
+            admin: caller.clone(),
             config: config.clone(),
             created_at: env.ledger().timestamp(),
             total_tokens: 0,
@@ -67,6 +98,8 @@ impl Factory {
         };
 
         <DataKey as Storage>::set_collection_info(env, collection_id, &info);
+        <DataKey as Storage>::set_collection_contract_wasm(env, collection_id, &wasm_hash);
+        <DataKey as Storage>::add_creator_collection(env, caller, collection_id);
 
         if let Some(recipient) = initial_royalty_recipient {
             let royalty = crate::storage::RoyaltyInfo {
@@ -77,9 +110,19 @@ impl Factory {
         }
 
         factory_config.total_collections += 1;
-        factory_config.accumulated_fees += factory_config.factory_fee;
+        factory_config.accumulated_fees = factory_config
+            .accumulated_fees
+            .checked_add(factory_config.factory_fee)
+            .ok_or(Error::Overflow)?;
         <DataKey as Storage>::set_factory_config(env, &factory_config);
 
+        events::collection_created(
+            env,
+            collection_id,
+            caller,
+            &collection_address,
+            factory_config.factory_fee,
+        );
         Ok(collection_id)
     }
 
@@ -98,77 +141,373 @@ impl Factory {
         <DataKey as Storage>::get_collection_info(env, collection_id)
     }
 
+    // Collection IDs are assigned sequentially from 1, so the directory is
+    // paged by position; collections not matching `status` are skipped
+    pub fn list_collections(
+        env: &Env,
+        start: u32,
+        limit: u32,
+        status: Option<CollectionStatus>,
+    ) -> Result<Vec<u64>, Error> {
+        let total = Self::get_collection_count(env)?;
+        let end = total.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut collection_ids = Vec::new(env);
+        for index in start..end {
+            let collection_id = index as u64 + 1;
+            if Self::matches_status(env, collection_id, &status) {
+                collection_ids.push_back(collection_id);
+            }
+        }
+        Ok(collection_ids)
+    }
+
+    pub fn collections_by_creator(
+        env: &Env,
+        creator: &Address,
+        start: u32,
+        limit: u32,
+        status: Option<CollectionStatus>,
+    ) -> Vec<u64> {
+        let total = <DataKey as Storage>::get_creator_collection_count(env, creator);
+        let end = total.min(start.saturating_add(limit.min(MAX_PAGE_SIZE)));
+
+        let mut collection_ids = Vec::new(env);
+        for index in start..end {
+            if let Some(collection_id) =
+                <DataKey as Storage>::get_creator_collection(env, creator, index)
+            {
+                if Self::matches_status(env, collection_id, &status) {
+                    collection_ids.push_back(collection_id);
+                }
+            }
+        }
+        collection_ids
+    }
+
     pub fn get_factory_config(env: &Env) -> Result<FactoryConfig, Error> {
         <DataKey as Storage>::get_factory_config(env)
     }
 
+    pub fn get_collection_wasm_hash(env: &Env) -> Option<BytesN<32>> {
+        <DataKey as Storage>::get_collection_wasm_hash(env)
+    }
+
     // ─────────────────────────────────────────────
     // Admin
     // ─────────────────────────────────────────────
+    pub fn set_collection_wasm_hash(
+        env: &Env,
+        caller: &Address,
+        wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Self::require_owner(env, caller)?;
+
+        <DataKey as Storage>::set_collection_wasm_hash(env, &wasm_hash);
+
+        events::collection_wasm_updated(env, &wasm_hash);
+        Ok(())
+    }
+
     pub fn set_factory_fee(env: &Env, caller: &Address, fee: i128) -> Result<(), Error> {
-        let mut config = <DataKey as Storage>::get_factory_config(env)?;
+        let mut config = Self::require_owner(env, caller)?;
 
-        if &config.owner != caller {
-            return Err(Error::Unauthorized);
+        if fee < 0 {
+            return Err(Error::InvalidInput);
         }
 
-        let _old_fee = config.factory_fee;
+        let old_fee = config.factory_fee;
         config.factory_fee = fee;
         <DataKey as Storage>::set_factory_config(env, &config);
 
+        events::fee_updated(env, old_fee, fee);
+        Ok(())
+    }
+
+    pub fn set_fee_token(env: &Env, caller: &Address, fee_token: Address) -> Result<(), Error> {
+        let mut config = Self::require_owner(env, caller)?;
+
+        // Fees already collected are held in the current token, and mint
+        // prices are charged in it. Priced vouchers signed for the current
+        // token stop being redeemable once it changes.
+        if config.accumulated_fees != 0 || <DataKey as Storage>::get_paid_mint_collections(env) != 0
+        {
+            return Err(Error::InvalidConfig);
+        }
+
+        config.fee_token = Some(fee_token.clone());
+        <DataKey as Storage>::set_factory_config(env, &config);
+
+        events::fee_token_updated(env, &fee_token);
+        Ok(())
+    }
+
+    // Share of paid mints, in basis points, kept by the platform
+    pub fn set_platform_fee_bps(env: &Env, caller: &Address, bps: u32) -> Result<(), Error> {
+        let mut config = Self::require_owner(env, caller)?;
+
+        if bps as i128 > BPS_DENOMINATOR {
+            return Err(Error::InvalidInput);
+        }
+
+        let old_bps = config.platform_fee_bps;
+        config.platform_fee_bps = bps;
+        <DataKey as Storage>::set_factory_config(env, &config);
+
+        events::platform_fee_updated(env, old_bps, bps);
         Ok(())
     }
 
     pub fn withdraw_fees(
         env: &Env,
         caller: &Address,
-        _recipient: Address,
+        recipient: Address,
         amount: i128,
     ) -> Result<(), Error> {
-        let mut config = <DataKey as Storage>::get_factory_config(env)?;
+        let mut config = Self::require_owner(env, caller)?;
 
-        if &config.owner != caller {
-            return Err(Error::Unauthorized);
+        if amount <= 0 {
+            return Err(Error::InvalidInput);
         }
 
         if config.accumulated_fees < amount {
             return Err(Error::InsufficientFee);
         }
 
+        let fee_token = config.fee_token.clone().ok_or(Error::FeeTokenNotSet)?;
+
         config.accumulated_fees -= amount;
         <DataKey as Storage>::set_factory_config(env, &config);
 
+        token::Client::new(env, &fee_token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount,
+        );
+
+        events::fees_withdrawn(env, &recipient, amount);
         Ok(())
     }
 
     pub fn set_max_collections(env: &Env, caller: &Address, max: Option<u32>) -> Result<(), Error> {
-        let mut config = <DataKey as Storage>::get_factory_config(env)?;
-
-        if &config.owner != caller {
-            return Err(Error::Unauthorized);
-        }
+        let mut config = Self::require_owner(env, caller)?;
 
         config.max_collections = max;
         <DataKey as Storage>::set_factory_config(env, &config);
+
+        events::max_collections_updated(env, max);
         Ok(())
     }
 
     pub fn set_factory_active(env: &Env, caller: &Address, active: bool) -> Result<(), Error> {
+        let mut config = Self::require_owner(env, caller)?;
+
+        config.is_active = active;
+        <DataKey as Storage>::set_factory_config(env, &config);
+
+        events::factory_active_updated(env, active);
+        Ok(())
+    }
+
+    // ─────────────────────────────────────────────
+    // Upgrades
+    // ─────────────────────────────────────────────
+    // Replaces the factory's own Wasm. The factory cannot read its current
+    // hash, so old_wasm_hash is only known after the first upgrade.
+    pub fn upgrade(env: &Env, caller: &Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Self::require_owner(env, caller)?;
+
+        let old_wasm_hash = <DataKey as Storage>::get_factory_wasm_hash(env);
+        <DataKey as Storage>::set_factory_wasm_hash(env, &new_wasm_hash);
+
+        events::factory_upgraded(env, &old_wasm_hash, &new_wasm_hash);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    // ─────────────────────────────────────────────
+    // Migration
+    // ─────────────────────────────────────────────
+    pub fn schema_version(env: &Env) -> u32 {
+        <DataKey as Storage>::get_schema_version(env)
+    }
+
+    // Brings stored entries up to SCHEMA_VERSION, at most `limit` steps per
+    // call. Returns true once the migration is complete; other calls fail
+    // with MigrationRequired until then.
+    pub fn migrate(env: &Env, caller: &Address, limit: u32) -> Result<bool, Error> {
+        let from_schema = <DataKey as Storage>::get_schema_version(env);
+        if from_schema >= SCHEMA_VERSION {
+            Self::require_owner(env, caller)?;
+            return Ok(true);
+        }
+
+        // The current FactoryConfig is unreadable until the end, so the owner
+        // is checked against the v1 config
+        caller.require_auth();
+
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            return Err(Error::InvalidInput);
+        }
+        if &migration::v1_owner(env)? != caller {
+            return Err(Error::Unauthorized);
+        }
+
+        let done = migration::migrate_v1(env, limit)?;
+        if done {
+            <DataKey as Storage>::set_schema_version(env, SCHEMA_VERSION);
+            events::schema_migrated(env, from_schema, SCHEMA_VERSION);
+        }
+        Ok(done)
+    }
+
+    // Moves address-keyed v1 entries, which migrate() cannot enumerate, to
+    // their v2 keys. Callable by the owner before or after migrate() has
+    // finished; returns how many of `keys` were still waiting to be moved.
+    pub fn migrate_entries(
+        env: &Env,
+        caller: &Address,
+        keys: Vec<LegacyKey>,
+    ) -> Result<u32, Error> {
+        if <DataKey as Storage>::get_schema_version(env) >= SCHEMA_VERSION {
+            Self::require_owner(env, caller)?;
+        } else {
+            caller.require_auth();
+            if &migration::v1_owner(env)? != caller {
+                return Err(Error::Unauthorized);
+            }
+        }
+
+        if keys.is_empty() || keys.len() > MAX_PAGE_SIZE {
+            return Err(Error::InvalidInput);
+        }
+        Ok(migration::migrate_entries(env, keys))
+    }
+
+    // ─────────────────────────────────────────────
+    // Ownership
+    // ─────────────────────────────────────────────
+    pub fn propose_owner(
+        env: &Env,
+        caller: &Address,
+        new_owner: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        let config = Self::require_owner(env, caller)?;
+
+        if expiration_ledger <= env.ledger().sequence() {
+            return Err(Error::InvalidInput);
+        }
+
+        let pending = PendingTransfer {
+            new_admin: new_owner.clone(),
+            expiration_ledger,
+        };
+        <DataKey as Storage>::set_pending_owner(env, &pending);
+
+        events::owner_proposed(env, &config.owner, &new_owner, expiration_ledger);
+        Ok(())
+    }
+
+    pub fn accept_owner(env: &Env, caller: &Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let pending =
+            <DataKey as Storage>::get_pending_owner(env).ok_or(Error::NoPendingTransfer)?;
+        if &pending.new_admin != caller {
+            return Err(Error::Unauthorized);
+        }
+        if env.ledger().sequence() > pending.expiration_ledger {
+            return Err(Error::TransferOfferExpired);
+        }
+
         let mut config = <DataKey as Storage>::get_factory_config(env)?;
+        let old_owner = config.owner;
+        config.owner = caller.clone();
+        <DataKey as Storage>::set_factory_config(env, &config);
+        <DataKey as Storage>::remove_pending_owner(env);
 
+        events::owner_transferred(env, &old_owner, caller);
+        Ok(())
+    }
+
+    pub fn cancel_owner_proposal(env: &Env, caller: &Address) -> Result<(), Error> {
+        Self::require_owner(env, caller)?;
+
+        let pending =
+            <DataKey as Storage>::get_pending_owner(env).ok_or(Error::NoPendingTransfer)?;
+        <DataKey as Storage>::remove_pending_owner(env);
+
+        events::owner_proposal_cancelled(env, &pending.new_admin);
+        Ok(())
+    }
+
+    pub fn get_pending_owner(env: &Env) -> Option<PendingTransfer> {
+        <DataKey as Storage>::get_pending_owner(env)
+    }
+
+    // ─────────────────────────────────────────────
+    // Authorization
+    // ─────────────────────────────────────────────
+    fn require_owner(env: &Env, caller: &Address) -> Result<FactoryConfig, Error> {
+        caller.require_auth();
+
+        let config = <DataKey as Storage>::get_factory_config(env)?;
         if &config.owner != caller {
             return Err(Error::Unauthorized);
         }
 
-        config.is_active = active;
-        <DataKey as Storage>::set_factory_config(env, &config);
+        Ok(config)
+    }
+
+    // ─────────────────────────────────────────────
+    // Fees
+    // ─────────────────────────────────────────────
+    fn charge_creation_fee(
+        env: &Env,
+        caller: &Address,
+        config: &FactoryConfig,
+    ) -> Result<(), Error> {
+        if config.factory_fee == 0 {
+            return Ok(());
+        }
+
+        let fee_token = config.fee_token.clone().ok_or(Error::FeeTokenNotSet)?;
+        let client = token::Client::new(env, &fee_token);
+
+        if client.balance(caller) < config.factory_fee {
+            return Err(Error::InsufficientFee);
+        }
+
+        client.transfer(caller, env.current_contract_address(), &config.factory_fee);
         Ok(())
     }
 
+    // ─────────────────────────────────────────────
+    // Deployment
+    // ─────────────────────────────────────────────
+    fn collection_salt(env: &Env, collection_id: u64) -> BytesN<32> {
+        let mut salt = [0u8; 32];
+        salt[24..].copy_from_slice(&collection_id.to_be_bytes());
+        BytesN::from_array(env, &salt)
+    }
+
+    // ─────────────────────────────────────────────
+    // Listing
+    // ─────────────────────────────────────────────
+    fn matches_status(env: &Env, collection_id: u64, status: &Option<CollectionStatus>) -> bool {
+        let is_paused = <DataKey as Storage>::is_collection_paused(env, collection_id);
+        match status {
+            None => true,
+            Some(CollectionStatus::Active) => !is_paused,
+            Some(CollectionStatus::Paused) => is_paused,
+        }
+    }
+
     // ─────────────────────────────────────────────
     // Validation
     // ─────────────────────────────────────────────
-    fn validate_collection_config(config: &CollectionConfig) -> Result<(), Error> {
+    pub(crate) fn validate_collection_config(config: &CollectionConfig) -> Result<(), Error> {
         if config.name.is_empty() || config.symbol.is_empty() {
             return Err(Error::InvalidConfig);
         }
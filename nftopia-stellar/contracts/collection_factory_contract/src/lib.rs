@@ -1,17 +1,27 @@
 #![no_std]
 #![allow(clippy::too_many_arguments)]
 
-use soroban_sdk::{contract, contractimpl, Address, Env, Map, String, Vec};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Map, String, Vec};
 
 mod collection;
 mod errors;
+mod events;
 mod factory;
+mod merkle;
+mod migration;
+mod schema;
+mod signature;
 mod storage;
 
 use collection::Collection;
 use errors::Error;
 use factory::Factory;
-use storage::{CollectionConfig, MetadataSchema, TokenMetadata};
+use migration::LegacyKey;
+use storage::{
+    AttributeDefinition, CollectionConfig, CollectionConfigUpdate, CollectionStatus,
+    MetadataSchema, MintPhase, MintPrice, MintVoucher, PendingTransfer, Role, TokenMetadata,
+    TokenUriConfig, TransferPolicy,
+};
 
 #[contract]
 pub struct CollectionFactoryContract;
@@ -40,6 +50,7 @@ impl CollectionFactoryContract {
         metadata_schema: u32,
         is_pausable: bool,
         is_upgradeable: bool,
+        transfer_policy: u32,
     ) -> Result<u64, Error> {
         // Convert metadata_schema u32 to MetadataSchema enum
         let schema = match metadata_schema {
@@ -49,6 +60,13 @@ impl CollectionFactoryContract {
             _ => return Err(Error::InvalidConfig),
         };
 
+        let transfer_policy = match transfer_policy {
+            0 => TransferPolicy::Transferable,
+            1 => TransferPolicy::Soulbound,
+            2 => TransferPolicy::AdminTransferOnly,
+            _ => return Err(Error::InvalidConfig),
+        };
+
         let config = CollectionConfig {
             name,
             symbol,
@@ -61,6 +79,7 @@ impl CollectionFactoryContract {
             metadata_schema: schema,
             is_pausable,
             is_upgradeable,
+            transfer_policy,
         };
 
         Factory::create_collection(&env, &caller, config, Some(royalty_recipient))
@@ -81,14 +100,69 @@ impl CollectionFactoryContract {
         Factory::get_collection_info(&env, collection_id)
     }
 
+    pub fn list_collections(
+        env: Env,
+        start: u32,
+        limit: u32,
+        status: Option<CollectionStatus>,
+    ) -> Result<Vec<u64>, Error> {
+        Factory::list_collections(&env, start, limit, status)
+    }
+
+    pub fn collections_by_creator(
+        env: Env,
+        creator: Address,
+        start: u32,
+        limit: u32,
+        status: Option<CollectionStatus>,
+    ) -> Vec<u64> {
+        Factory::collections_by_creator(&env, &creator, start, limit, status)
+    }
+
     pub fn get_factory_config(env: Env) -> Result<storage::FactoryConfig, Error> {
         Factory::get_factory_config(&env)
     }
 
+    pub fn get_collection_wasm_hash(env: Env) -> Option<BytesN<32>> {
+        Factory::get_collection_wasm_hash(&env)
+    }
+
+    pub fn set_collection_wasm_hash(
+        env: Env,
+        caller: Address,
+        wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Factory::set_collection_wasm_hash(&env, &caller, wasm_hash)
+    }
+
+    pub fn upgrade(env: Env, caller: Address, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        Factory::upgrade(&env, &caller, new_wasm_hash)
+    }
+
+    pub fn schema_version(env: Env) -> u32 {
+        Factory::schema_version(&env)
+    }
+
+    pub fn migrate(env: Env, caller: Address, limit: u32) -> Result<bool, Error> {
+        Factory::migrate(&env, &caller, limit)
+    }
+
+    pub fn migrate_entries(env: Env, caller: Address, keys: Vec<LegacyKey>) -> Result<u32, Error> {
+        Factory::migrate_entries(&env, &caller, keys)
+    }
+
     pub fn set_factory_fee(env: Env, caller: Address, fee: i128) -> Result<(), Error> {
         Factory::set_factory_fee(&env, &caller, fee)
     }
 
+    pub fn set_fee_token(env: Env, caller: Address, fee_token: Address) -> Result<(), Error> {
+        Factory::set_fee_token(&env, &caller, fee_token)
+    }
+
+    pub fn set_platform_fee_bps(env: Env, caller: Address, bps: u32) -> Result<(), Error> {
+        Factory::set_platform_fee_bps(&env, &caller, bps)
+    }
+
     pub fn withdraw_fees(
         env: Env,
         caller: Address,
@@ -98,6 +172,27 @@ impl CollectionFactoryContract {
         Factory::withdraw_fees(&env, &caller, recipient, amount)
     }
 
+    pub fn propose_owner(
+        env: Env,
+        caller: Address,
+        new_owner: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        Factory::propose_owner(&env, &caller, new_owner, expiration_ledger)
+    }
+
+    pub fn accept_owner(env: Env, caller: Address) -> Result<(), Error> {
+        Factory::accept_owner(&env, &caller)
+    }
+
+    pub fn cancel_owner_proposal(env: Env, caller: Address) -> Result<(), Error> {
+        Factory::cancel_owner_proposal(&env, &caller)
+    }
+
+    pub fn get_pending_owner(env: Env) -> Option<PendingTransfer> {
+        Factory::get_pending_owner(&env)
+    }
+
     pub fn set_max_collections(env: Env, caller: Address, max: Option<u32>) -> Result<(), Error> {
         Factory::set_max_collections(&env, &caller, max)
     }
@@ -117,6 +212,49 @@ impl CollectionFactoryContract {
         Collection::mint(&env, collection_id, &to, uri, attributes)
     }
 
+    pub fn minter_mint(
+        env: Env,
+        collection_id: u64,
+        minter: Address,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+    ) -> Result<u32, Error> {
+        Collection::minter_mint(&env, collection_id, &minter, &to, uri, attributes)
+    }
+
+    pub fn set_minter_key(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        public_key: BytesN<32>,
+        authorized: bool,
+    ) -> Result<(), Error> {
+        Collection::set_minter_key(&env, collection_id, &caller, &public_key, authorized)
+    }
+
+    pub fn redeem_voucher(
+        env: Env,
+        collection_id: u64,
+        voucher: MintVoucher,
+        signature: BytesN<64>,
+        buyer: Address,
+    ) -> Result<u32, Error> {
+        Collection::redeem_voucher(&env, collection_id, voucher, &signature, &buyer)
+    }
+
+    pub fn mint_with_proof(
+        env: Env,
+        collection_id: u64,
+        to: Address,
+        uri: String,
+        attributes: Option<Map<String, String>>,
+        proof: Vec<BytesN<32>>,
+        allowance: u32,
+    ) -> Result<u32, Error> {
+        Collection::mint_with_proof(&env, collection_id, &to, uri, attributes, proof, allowance)
+    }
+
     pub fn batch_mint(
         env: Env,
         collection_id: u64,
@@ -157,8 +295,16 @@ impl CollectionFactoryContract {
         caller: Address,
         approved: Address,
         token_id: u32,
+        live_until_ledger: u32,
     ) -> Result<(), Error> {
-        Collection::approve(&env, collection_id, &caller, &approved, token_id)
+        Collection::approve(
+            &env,
+            collection_id,
+            &caller,
+            &approved,
+            token_id,
+            live_until_ledger,
+        )
     }
 
     pub fn set_approval_for_all(
@@ -166,9 +312,47 @@ impl CollectionFactoryContract {
         collection_id: u64,
         caller: Address,
         operator: Address,
-        approved: bool,
+        live_until_ledger: u32,
     ) -> Result<(), Error> {
-        Collection::set_approval_for_all(&env, collection_id, &caller, &operator, approved)
+        Collection::set_approval_for_all(&env, collection_id, &caller, &operator, live_until_ledger)
+    }
+
+    pub fn revoke_all_approvals(env: Env, owner: Address) -> Result<(), Error> {
+        Collection::revoke_all_approvals(&env, &owner)
+    }
+
+    // Permits are checked against an ed25519 key the owner registers here
+    // once, not against the owner account's own signers
+    pub fn set_permit_key(
+        env: Env,
+        owner: Address,
+        public_key: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        Collection::set_permit_key(&env, &owner, public_key)
+    }
+
+    pub fn permit(
+        env: Env,
+        collection_id: u64,
+        owner: Address,
+        spender: Address,
+        token_id: u32,
+        deadline: u32,
+        nonce: u64,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        Collection::permit(
+            &env,
+            collection_id,
+            &owner,
+            &spender,
+            token_id,
+            deadline,
+            nonce,
+            &public_key,
+            &signature,
+        )
     }
 
     pub fn set_royalty_info(
@@ -181,6 +365,25 @@ impl CollectionFactoryContract {
         Collection::set_royalty_info(&env, collection_id, &caller, recipient, percentage)
     }
 
+    pub fn set_royalty_splits(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        shares: Vec<storage::RoyaltyShare>,
+    ) -> Result<(), Error> {
+        Collection::set_royalty_splits(&env, collection_id, &caller, shares)
+    }
+
+    pub fn set_token_royalty_info(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        token_id: u32,
+        royalty: Option<storage::RoyaltyInfo>,
+    ) -> Result<(), Error> {
+        Collection::set_token_royalty_info(&env, collection_id, &caller, token_id, royalty)
+    }
+
     pub fn set_whitelist(
         env: Env,
         collection_id: u64,
@@ -191,6 +394,84 @@ impl CollectionFactoryContract {
         Collection::set_whitelist(&env, collection_id, &caller, &address, whitelisted)
     }
 
+    pub fn set_mint_price(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        price: Option<MintPrice>,
+    ) -> Result<(), Error> {
+        Collection::set_mint_price(&env, collection_id, &caller, price)
+    }
+
+    pub fn mint_price(env: Env, collection_id: u64) -> Option<MintPrice> {
+        Collection::mint_price(&env, collection_id)
+    }
+
+    pub fn set_mint_phases(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        phases: Vec<MintPhase>,
+    ) -> Result<(), Error> {
+        Collection::set_mint_phases(&env, collection_id, &caller, phases)
+    }
+
+    pub fn mint_phases(env: Env, collection_id: u64) -> Vec<MintPhase> {
+        Collection::mint_phases(&env, collection_id)
+    }
+
+    pub fn active_phase(env: Env, collection_id: u64) -> Option<u32> {
+        Collection::active_phase(&env, collection_id)
+    }
+
+    pub fn set_merkle_root(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        phase: u32,
+        root: BytesN<32>,
+    ) -> Result<(), Error> {
+        Collection::set_merkle_root(&env, collection_id, &caller, phase, root)
+    }
+
+    pub fn merkle_root(env: Env, collection_id: u64, phase: u32) -> Option<BytesN<32>> {
+        Collection::merkle_root(&env, collection_id, phase)
+    }
+
+    pub fn allowlist_minted(env: Env, collection_id: u64, phase: u32, address: Address) -> u32 {
+        Collection::allowlist_minted(&env, collection_id, phase, &address)
+    }
+
+    pub fn update_collection_config(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        update: CollectionConfigUpdate,
+    ) -> Result<(), Error> {
+        Collection::update_collection_config(&env, collection_id, &caller, update)
+    }
+
+    pub fn upgrade_collection(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        Collection::upgrade_collection(&env, collection_id, &caller, new_wasm_hash)
+    }
+
+    pub fn renounce_upgradeability(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+    ) -> Result<(), Error> {
+        Collection::renounce_upgradeability(&env, collection_id, &caller)
+    }
+
+    pub fn collection_contract_wasm(env: Env, collection_id: u64) -> Option<BytesN<32>> {
+        Collection::collection_contract_wasm(&env, collection_id)
+    }
+
     pub fn set_paused(
         env: Env,
         collection_id: u64,
@@ -200,8 +481,78 @@ impl CollectionFactoryContract {
         Collection::set_paused(&env, collection_id, &caller, paused)
     }
 
+    pub fn grant_role(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        Collection::grant_role(&env, collection_id, &caller, role, &account)
+    }
+
+    pub fn revoke_role(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        role: Role,
+        account: Address,
+    ) -> Result<(), Error> {
+        Collection::revoke_role(&env, collection_id, &caller, role, &account)
+    }
+
+    pub fn has_role(env: Env, collection_id: u64, role: Role, account: Address) -> bool {
+        Collection::has_role(&env, collection_id, role, &account)
+    }
+
+    pub fn role_members(
+        env: Env,
+        collection_id: u64,
+        role: Role,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Address> {
+        Collection::role_members(&env, collection_id, role, start, limit)
+    }
+
+    pub fn propose_collection_admin(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        new_admin: Address,
+        expiration_ledger: u32,
+    ) -> Result<(), Error> {
+        Collection::propose_collection_admin(
+            &env,
+            collection_id,
+            &caller,
+            new_admin,
+            expiration_ledger,
+        )
+    }
+
+    pub fn accept_collection_admin(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+    ) -> Result<(), Error> {
+        Collection::accept_collection_admin(&env, collection_id, &caller)
+    }
+
+    pub fn cancel_collection_admin_proposal(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+    ) -> Result<(), Error> {
+        Collection::cancel_collection_admin_proposal(&env, collection_id, &caller)
+    }
+
+    pub fn get_pending_collection_admin(env: Env, collection_id: u64) -> Option<PendingTransfer> {
+        Collection::get_pending_collection_admin(&env, collection_id)
+    }
+
     // Query functions
-    pub fn balance_of(env: Env, collection_id: u64, address: Address) -> u32 {
+    pub fn balance_of(env: Env, collection_id: u64, address: Address) -> Result<u32, Error> {
         Collection::balance_of(&env, collection_id, &address)
     }
 
@@ -209,16 +560,36 @@ impl CollectionFactoryContract {
         Collection::owner_of(&env, collection_id, token_id)
     }
 
-    pub fn get_approved(env: Env, collection_id: u64, token_id: u32) -> Option<Address> {
+    pub fn get_approved(
+        env: Env,
+        collection_id: u64,
+        token_id: u32,
+    ) -> Result<Option<Address>, Error> {
         Collection::get_approved(&env, collection_id, token_id)
     }
 
+    pub fn permit_nonce(env: Env, owner: Address) -> u64 {
+        Collection::permit_nonce(&env, &owner)
+    }
+
+    pub fn permit_key(env: Env, owner: Address) -> Option<BytesN<32>> {
+        Collection::permit_key(&env, &owner)
+    }
+
+    pub fn is_minter_key(env: Env, collection_id: u64, public_key: BytesN<32>) -> bool {
+        Collection::is_minter_key(&env, collection_id, &public_key)
+    }
+
+    pub fn is_voucher_redeemed(env: Env, collection_id: u64, voucher_id: BytesN<32>) -> bool {
+        Collection::is_voucher_redeemed(&env, collection_id, &voucher_id)
+    }
+
     pub fn is_approved_for_all(
         env: Env,
         collection_id: u64,
         owner: Address,
         operator: Address,
-    ) -> bool {
+    ) -> Result<bool, Error> {
         Collection::is_approved_for_all(&env, collection_id, &owner, &operator)
     }
 
@@ -234,6 +605,83 @@ impl CollectionFactoryContract {
         Collection::token_metadata(&env, collection_id, token_id)
     }
 
+    pub fn update_token_metadata(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        token_id: u32,
+        uri: String,
+        attributes: Map<String, String>,
+    ) -> Result<(), Error> {
+        Collection::update_token_metadata(&env, collection_id, &caller, token_id, uri, attributes)
+    }
+
+    pub fn set_base_uri(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        base_uri: String,
+    ) -> Result<(), Error> {
+        Collection::set_base_uri(&env, collection_id, &caller, base_uri)
+    }
+
+    pub fn set_token_uri_config(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        suffix: String,
+        placeholder_uri: Option<String>,
+        provenance_hash: Option<BytesN<32>>,
+    ) -> Result<(), Error> {
+        Collection::set_token_uri_config(
+            &env,
+            collection_id,
+            &caller,
+            suffix,
+            placeholder_uri,
+            provenance_hash,
+        )
+    }
+
+    pub fn token_uri_config(env: Env, collection_id: u64) -> Option<TokenUriConfig> {
+        Collection::token_uri_config(&env, collection_id)
+    }
+
+    pub fn reveal(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        revealed_base_uri: String,
+    ) -> Result<(), Error> {
+        Collection::reveal(&env, collection_id, &caller, revealed_base_uri)
+    }
+
+    pub fn set_attribute_schema(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        definitions: Vec<AttributeDefinition>,
+    ) -> Result<(), Error> {
+        Collection::set_attribute_schema(&env, collection_id, &caller, definitions)
+    }
+
+    pub fn attribute_schema(env: Env, collection_id: u64) -> Vec<AttributeDefinition> {
+        Collection::attribute_schema(&env, collection_id)
+    }
+
+    pub fn freeze_metadata(
+        env: Env,
+        collection_id: u64,
+        caller: Address,
+        token_id: Option<u32>,
+    ) -> Result<(), Error> {
+        Collection::freeze_metadata(&env, collection_id, &caller, token_id)
+    }
+
+    pub fn is_metadata_frozen(env: Env, collection_id: u64, token_id: Option<u32>) -> bool {
+        Collection::is_metadata_frozen(&env, collection_id, token_id)
+    }
+
     pub fn total_supply(env: Env, collection_id: u64) -> Result<u32, Error> {
         Collection::total_supply(&env, collection_id)
     }
@@ -242,6 +690,59 @@ impl CollectionFactoryContract {
         Collection::royalty_info(&env, collection_id)
     }
 
+    pub fn token_royalty_info(
+        env: Env,
+        collection_id: u64,
+        token_id: u32,
+    ) -> Option<storage::RoyaltyInfo> {
+        Collection::token_royalty_info(&env, collection_id, token_id)
+    }
+
+    pub fn royalty_splits(env: Env, collection_id: u64) -> Vec<storage::RoyaltyShare> {
+        Collection::royalty_splits(&env, collection_id)
+    }
+
+    pub fn royalty_payouts_for_sale(
+        env: Env,
+        collection_id: u64,
+        token_id: u32,
+        sale_price: i128,
+    ) -> Result<Vec<(Address, i128)>, Error> {
+        Collection::royalty_payouts_for_sale(&env, collection_id, token_id, sale_price)
+    }
+
+    pub fn royalty_info_for_sale(
+        env: Env,
+        collection_id: u64,
+        token_id: u32,
+        sale_price: i128,
+    ) -> Result<(Address, i128), Error> {
+        Collection::royalty_info_for_sale(&env, collection_id, token_id, sale_price)
+    }
+
+    pub fn tokens_of_owner(
+        env: Env,
+        collection_id: u64,
+        owner: Address,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<u32>, Error> {
+        Collection::tokens_of_owner(&env, collection_id, &owner, start, limit)
+    }
+
+    pub fn token_by_index(env: Env, collection_id: u64, index: u32) -> Result<u32, Error> {
+        Collection::token_by_index(&env, collection_id, index)
+    }
+
+    pub fn tokens(
+        env: Env,
+        collection_id: u64,
+        cursor: u32,
+        limit: u32,
+    ) -> Result<Vec<u32>, Error> {
+        Collection::tokens(&env, collection_id, cursor, limit)
+    }
+
     pub fn transfer_from(
         env: Env,
         collection_id: u64,
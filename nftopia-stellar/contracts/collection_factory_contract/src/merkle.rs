@@ -0,0 +1,26 @@
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
+
+// Allowlist leaves commit to an address and the number of tokens it may mint:
+// sha256(xdr(address) || allowance as big-endian u32).
+pub fn leaf(env: &Env, account: &Address, allowance: u32) -> BytesN<32> {
+    let mut data = account.clone().to_xdr(env);
+    data.extend_from_array(&allowance.to_be_bytes());
+    env.crypto().sha256(&data).into()
+}
+
+// Pairs are hashed in sorted order, so proofs carry no left/right flags.
+pub fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+
+    let mut data = Bytes::from_array(env, &first.to_array());
+    data.extend_from_array(&second.to_array());
+    env.crypto().sha256(&data).into()
+}
+
+pub fn verify(env: &Env, root: &BytesN<32>, leaf: BytesN<32>, proof: &Vec<BytesN<32>>) -> bool {
+    let mut computed = leaf;
+    for sibling in proof.iter() {
+        computed = hash_pair(env, &computed, &sibling);
+    }
+    &computed == root
+}
@@ -0,0 +1,347 @@
+use soroban_sdk::{contracttype, Address, Env, Map, String, Symbol, TryFromVal, Val, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{
+        read_instance, CollectionConfig, CollectionInfo, DataKey, FactoryConfig, MetadataSchema,
+        MigrationCursor, OperatorApproval, RoyaltyInfo, Storage, TokenApproval, TokenMetadata,
+        TransferPolicy,
+    },
+};
+
+// Schema v1 layouts, as written before the schema was versioned. v1 kept
+// every entry in instance storage under these keys; they encode the same as
+// the v1 DataKey, so the entries are found where v1 left them. v1 had no
+// enumeration or creator indexes, so those are rebuilt while walking.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKeyV1 {
+    FactoryConfig,
+    CollectionInfo(u64),
+    NextTokenId(u64),
+    TokenOwner(u64, u32),
+    TokenMetadata(u64, u32),
+    Balance(u64, Address),
+    Approved(u64, u32),
+    ApprovedForAll(u64, Address, Address),
+    RoyaltyInfo(u64),
+    WhitelistForMint(u64, Address),
+    IsPaused(u64),
+}
+
+// v1 entries keyed by an arbitrary address. The walk cannot enumerate them,
+// so the owner reads them off the factory's instance entry and passes them to
+// migrate_entries. A zero balance left behind by v1 burns is only dropped.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LegacyKey {
+    Balance(u64, Address),
+    ApprovedForAll(u64, Address, Address),
+    WhitelistForMint(u64, Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryConfigV1 {
+    pub owner: Address,
+    pub factory_fee: i128,
+    pub max_collections: Option<u32>,
+    pub total_collections: u32,
+    pub accumulated_fees: i128,
+    pub is_active: bool,
+}
+
+// Unversioned factories that charged the creation fee in a token already
+// stored the fee token alongside the v1 fields
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryConfigV1WithFeeToken {
+    pub owner: Address,
+    pub factory_fee: i128,
+    pub fee_token: Option<Address>,
+    pub max_collections: Option<u32>,
+    pub total_collections: u32,
+    pub accumulated_fees: i128,
+    pub is_active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionConfigV1 {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    pub base_uri: String,
+    pub max_supply: Option<u32>,
+    pub is_public_mint: bool,
+    pub royalty_percentage: u32,
+    pub royalty_recipient: Address,
+    pub metadata_schema: MetadataSchema,
+    pub is_pausable: bool,
+    pub is_upgradeable: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionInfoV1 {
+    pub address: Address,
+    pub creator: Address,
+    pub config: CollectionConfigV1,
+    pub created_at: u64,
+    pub total_tokens: u32,
+    pub is_paused: bool,
+}
+
+pub fn v1_owner(env: &Env) -> Result<Address, Error> {
+    Ok(v1_factory_config(env)?.owner)
+}
+
+// Walks v1 entries forward, spending one step per collection and one per
+// token id. Returns true once everything is on v2; until then the position is
+// kept in a cursor so the next call resumes where this one stopped.
+pub fn migrate_v1(env: &Env, limit: u32) -> Result<bool, Error> {
+    let config = v1_factory_config(env)?;
+    let total_collections = config.total_collections as u64;
+
+    let mut cursor = <DataKey as Storage>::get_migration_cursor(env).unwrap_or(MigrationCursor {
+        collection_id: 1,
+        token_id: 0,
+        indexed: 0,
+    });
+
+    let mut steps = 0;
+    while steps < limit && cursor.collection_id <= total_collections {
+        let collection_id = cursor.collection_id;
+
+        if cursor.token_id == 0 {
+            migrate_collection(env, collection_id)?;
+            cursor.token_id = 1;
+        } else if cursor.token_id < <DataKey as Storage>::get_next_token_id(env, collection_id) {
+            if migrate_token(env, collection_id, cursor.token_id, cursor.indexed) {
+                cursor.indexed += 1;
+            }
+            cursor.token_id += 1;
+        } else {
+            cursor = MigrationCursor {
+                collection_id: collection_id + 1,
+                token_id: 0,
+                indexed: 0,
+            };
+            continue;
+        }
+
+        steps += 1;
+    }
+
+    if cursor.collection_id <= total_collections {
+        <DataKey as Storage>::set_migration_cursor(env, &cursor);
+        return Ok(false);
+    }
+
+    // The factory config goes last so the v1 owner stays readable throughout
+    <DataKey as Storage>::set_factory_config(env, &config);
+    <DataKey as Storage>::remove_migration_cursor(env);
+    Ok(true)
+}
+
+// Moves the given address-keyed v1 entries to their v2 keys. Returns how
+// many were still in instance storage.
+pub fn migrate_entries(env: &Env, keys: Vec<LegacyKey>) -> u32 {
+    let mut moved = 0;
+    for key in keys.iter() {
+        let found = match key {
+            LegacyKey::Balance(collection_id, owner) => migrate_balance(env, collection_id, &owner),
+            LegacyKey::ApprovedForAll(collection_id, owner, operator) => {
+                migrate_operator_approval(env, collection_id, &owner, &operator)
+            }
+            LegacyKey::WhitelistForMint(collection_id, address) => {
+                match take_v1(
+                    env,
+                    &DataKeyV1::WhitelistForMint(collection_id, address.clone()),
+                ) {
+                    Some(whitelisted) => {
+                        <DataKey as Storage>::set_whitelisted_for_mint(
+                            env,
+                            collection_id,
+                            &address,
+                            whitelisted,
+                        );
+                        true
+                    }
+                    None => false,
+                }
+            }
+        };
+        if found {
+            moved += 1;
+        }
+    }
+    moved
+}
+
+// Accepts both unversioned config layouts and returns it in the v2 shape
+fn v1_factory_config(env: &Env) -> Result<FactoryConfig, Error> {
+    let raw: Map<Symbol, Val> =
+        read_instance(env, &DataKey::FactoryConfig).ok_or(Error::StorageError)?;
+
+    // Unpacking a struct from a map of the other shape traps, so the layout
+    // is told apart by its fields first
+    if !raw.contains_key(Symbol::new(env, "fee_token")) {
+        let config =
+            FactoryConfigV1::try_from_val(env, &raw.to_val()).map_err(|_| Error::StorageError)?;
+        return Ok(FactoryConfig {
+            owner: config.owner,
+            factory_fee: config.factory_fee,
+            fee_token: None,
+            platform_fee_bps: 0,
+            max_collections: config.max_collections,
+            total_collections: config.total_collections,
+            accumulated_fees: config.accumulated_fees,
+            is_active: config.is_active,
+        });
+    }
+
+    let config = FactoryConfigV1WithFeeToken::try_from_val(env, &raw.to_val())
+        .map_err(|_| Error::StorageError)?;
+    Ok(FactoryConfig {
+        owner: config.owner,
+        factory_fee: config.factory_fee,
+        fee_token: config.fee_token,
+        platform_fee_bps: 0,
+        max_collections: config.max_collections,
+        total_collections: config.total_collections,
+        accumulated_fees: config.accumulated_fees,
+        is_active: config.is_active,
+    })
+}
+
+// Removes a v1 entry from instance storage and returns its value
+fn take_v1<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKeyV1) -> Option<V> {
+    let storage = env.storage().instance();
+    let value = storage.get(key);
+    if value.is_some() {
+        storage.remove(key);
+    }
+    value
+}
+
+// Moves the collection-level entries. v1 collections are administered by
+// their creator and freely transferable.
+fn migrate_collection(env: &Env, collection_id: u64) -> Result<(), Error> {
+    let info: CollectionInfoV1 =
+        take_v1(env, &DataKeyV1::CollectionInfo(collection_id)).ok_or(Error::CollectionNotFound)?;
+
+    let config = CollectionConfig {
+        name: info.config.name,
+        symbol: info.config.symbol,
+        description: info.config.description,
+        base_uri: info.config.base_uri,
+        max_supply: info.config.max_supply,
+        is_public_mint: info.config.is_public_mint,
+        royalty_percentage: info.config.royalty_percentage,
+        royalty_recipient: info.config.royalty_recipient,
+        metadata_schema: info.config.metadata_schema,
+        is_pausable: info.config.is_pausable,
+        is_upgradeable: info.config.is_upgradeable,
+        transfer_policy: TransferPolicy::Transferable,
+    };
+    let migrated = CollectionInfo {
+        address: info.address,
+        creator: info.creator.clone(),
+        admin: info.creator.clone(),
+        config,
+        created_at: info.created_at,
+        total_tokens: info.total_tokens,
+        is_paused: info.is_paused,
+    };
+
+    <DataKey as Storage>::set_collection_info(env, collection_id, &migrated);
+    <DataKey as Storage>::add_creator_collection(env, &info.creator, collection_id);
+
+    if let Some(next_id) = take_v1::<u32>(env, &DataKeyV1::NextTokenId(collection_id)) {
+        <DataKey as Storage>::set_next_token_id(env, collection_id, next_id);
+    }
+    if let Some(royalty) = take_v1::<RoyaltyInfo>(env, &DataKeyV1::RoyaltyInfo(collection_id)) {
+        <DataKey as Storage>::set_royalty_info(env, collection_id, &royalty);
+    }
+    if let Some(paused) = take_v1::<bool>(env, &DataKeyV1::IsPaused(collection_id)) {
+        <DataKey as Storage>::set_collection_paused(env, collection_id, paused);
+    }
+    Ok(())
+}
+
+// Moves a token's entries and adds it to the owner and collection
+// enumeration indexes. Burned tokens keep their metadata, as in v1. The
+// owner's position is tracked in a scratch counter that is dropped once it
+// reaches the balance, which is moved along with the owner's first token.
+fn migrate_token(env: &Env, collection_id: u64, token_id: u32, indexed: u32) -> bool {
+    if let Some(metadata) =
+        take_v1::<TokenMetadata>(env, &DataKeyV1::TokenMetadata(collection_id, token_id))
+    {
+        <DataKey as Storage>::set_token_metadata(env, collection_id, token_id, &metadata);
+    }
+
+    let approved = take_v1::<Address>(env, &DataKeyV1::Approved(collection_id, token_id));
+    let Some(owner) = take_v1::<Address>(env, &DataKeyV1::TokenOwner(collection_id, token_id))
+    else {
+        return false;
+    };
+    <DataKey as Storage>::set_token_owner(env, collection_id, token_id, &owner);
+
+    // v1 approvals never expired, so they run as long as an entry can
+    if let Some(approved) = approved {
+        let approval = TokenApproval {
+            approved,
+            live_until_ledger: env.ledger().max_live_until_ledger(),
+            epoch: <DataKey as Storage>::get_approval_epoch(env, &owner),
+        };
+        <DataKey as Storage>::set_token_approval(env, collection_id, token_id, &approval);
+    }
+
+    let index = <DataKey as Storage>::get_migrated_owner_tokens(env, collection_id, &owner);
+    if index == 0 {
+        migrate_balance(env, collection_id, &owner);
+    }
+    <DataKey as Storage>::set_owner_token(env, collection_id, &owner, index, token_id);
+    <DataKey as Storage>::set_owner_token_index(env, collection_id, token_id, index);
+    <DataKey as Storage>::set_token_by_index(env, collection_id, indexed, token_id);
+    <DataKey as Storage>::set_token_index(env, collection_id, token_id, indexed);
+
+    if index + 1 >= <DataKey as Storage>::get_balance(env, collection_id, &owner) {
+        <DataKey as Storage>::remove_migrated_owner_tokens(env, collection_id, &owner);
+    } else {
+        <DataKey as Storage>::set_migrated_owner_tokens(env, collection_id, &owner, index + 1);
+    }
+    true
+}
+
+fn migrate_balance(env: &Env, collection_id: u64, owner: &Address) -> bool {
+    let Some(balance) = take_v1::<u32>(env, &DataKeyV1::Balance(collection_id, owner.clone()))
+    else {
+        return false;
+    };
+    if balance > 0 {
+        <DataKey as Storage>::set_balance(env, collection_id, owner, balance);
+    }
+    true
+}
+
+fn migrate_operator_approval(
+    env: &Env,
+    collection_id: u64,
+    owner: &Address,
+    operator: &Address,
+) -> bool {
+    let key = DataKeyV1::ApprovedForAll(collection_id, owner.clone(), operator.clone());
+    let Some(approved) = take_v1::<bool>(env, &key) else {
+        return false;
+    };
+    if approved {
+        let approval = OperatorApproval {
+            live_until_ledger: env.ledger().max_live_until_ledger(),
+            epoch: <DataKey as Storage>::get_approval_epoch(env, owner),
+        };
+        <DataKey as Storage>::set_operator_approval(env, collection_id, owner, operator, &approval);
+    }
+    true
+}
@@ -0,0 +1,113 @@
+use soroban_sdk::{Map, String, Vec};
+
+use crate::{
+    errors::Error,
+    storage::{AttributeDefinition, AttributeType, MetadataSchema},
+};
+
+pub const MAX_ATTRIBUTE_KEY_LEN: u32 = 32;
+pub const MAX_ATTRIBUTE_VALUE_LEN: u32 = 256;
+
+// Timestamps are unix seconds and must fit in a u64
+const MAX_TIMESTAMP_DIGITS: usize = 19;
+
+// What each schema accepts:
+// - Basic: a uri only, no attributes
+// - Extended: attributes whose keys are declared in the collection's attribute
+//   schema, with string values of bounded length
+// - Advanced: as Extended, with each value also parsed as its declared type
+// Both Extended and Advanced reject tokens missing a required attribute.
+pub fn validate_attributes(
+    schema: &MetadataSchema,
+    definitions: &Vec<AttributeDefinition>,
+    attributes: &Map<String, String>,
+) -> Result<(), Error> {
+    if *schema == MetadataSchema::Basic {
+        if !attributes.is_empty() {
+            return Err(Error::InvalidMetadata);
+        }
+        return Ok(());
+    }
+
+    let typed = *schema == MetadataSchema::Advanced;
+
+    for (key, value) in attributes.iter() {
+        if value.len() > MAX_ATTRIBUTE_VALUE_LEN {
+            return Err(Error::InvalidMetadata);
+        }
+
+        let definition = definitions
+            .iter()
+            .find(|definition| definition.key == key)
+            .ok_or(Error::InvalidMetadata)?;
+
+        if typed && !value_matches(&value, &definition.value_type) {
+            return Err(Error::InvalidMetadata);
+        }
+    }
+
+    for definition in definitions.iter() {
+        if definition.required && !attributes.contains_key(definition.key) {
+            return Err(Error::InvalidMetadata);
+        }
+    }
+
+    Ok(())
+}
+
+// Checks a creator-registered attribute schema before it is stored
+pub fn validate_definitions(definitions: &Vec<AttributeDefinition>) -> Result<(), Error> {
+    for (index, definition) in definitions.iter().enumerate() {
+        if definition.key.is_empty() || definition.key.len() > MAX_ATTRIBUTE_KEY_LEN {
+            return Err(Error::InvalidConfig);
+        }
+
+        if let AttributeType::Enum(options) = &definition.value_type {
+            if options.is_empty() {
+                return Err(Error::InvalidConfig);
+            }
+        }
+
+        let duplicate = definitions
+            .iter()
+            .skip(index + 1)
+            .any(|other| other.key == definition.key);
+        if duplicate {
+            return Err(Error::InvalidConfig);
+        }
+    }
+
+    Ok(())
+}
+
+// Values are at most MAX_ATTRIBUTE_VALUE_LEN bytes, checked by the caller
+fn value_matches(value: &String, value_type: &AttributeType) -> bool {
+    let mut buf = [0u8; MAX_ATTRIBUTE_VALUE_LEN as usize];
+    let len = value.len() as usize;
+    value.copy_into_slice(&mut buf[..len]);
+    let bytes = &buf[..len];
+
+    match value_type {
+        AttributeType::Text => true,
+        AttributeType::Integer => is_integer(bytes),
+        AttributeType::Decimal => is_decimal(bytes),
+        AttributeType::Timestamp => is_digits(bytes) && bytes.len() <= MAX_TIMESTAMP_DIGITS,
+        AttributeType::Enum(options) => options.contains(value),
+    }
+}
+
+fn is_digits(bytes: &[u8]) -> bool {
+    !bytes.is_empty() && bytes.iter().all(u8::is_ascii_digit)
+}
+
+fn is_integer(bytes: &[u8]) -> bool {
+    is_digits(bytes.strip_prefix(b"-").unwrap_or(bytes))
+}
+
+fn is_decimal(bytes: &[u8]) -> bool {
+    let unsigned = bytes.strip_prefix(b"-").unwrap_or(bytes);
+    match unsigned.iter().position(|byte| *byte == b'.') {
+        Some(dot) => is_digits(&unsigned[..dot]) && is_digits(&unsigned[dot + 1..]),
+        None => is_digits(unsigned),
+    }
+}
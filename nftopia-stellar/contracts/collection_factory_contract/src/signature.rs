@@ -0,0 +1,43 @@
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, Env};
+
+use crate::storage::MintVoucher;
+
+const PERMIT_DOMAIN: &[u8] = b"nftopia:permit:v1";
+const VOUCHER_DOMAIN: &[u8] = b"nftopia:voucher:v1";
+
+// An owner's off-chain consent to approve `spender` for one token until the
+// `deadline` ledger. `nonce` must be the owner's next permit nonce.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Permit {
+    pub collection_id: u64,
+    pub owner: Address,
+    pub spender: Address,
+    pub token_id: u32,
+    pub deadline: u32,
+    pub nonce: u64,
+}
+
+// Signed messages are domain || network id || xdr(factory) || xdr(payload),
+// so a signature only verifies for one kind of message, on one network, at
+// one factory.
+pub fn message(env: &Env, domain: &[u8], payload: Bytes) -> Bytes {
+    let mut data = Bytes::from_slice(env, domain);
+    data.extend_from_array(&env.ledger().network_id().to_array());
+    data.append(&env.current_contract_address().to_xdr(env));
+    data.append(&payload);
+    data
+}
+
+pub fn permit_message(env: &Env, permit: &Permit) -> Bytes {
+    message(env, PERMIT_DOMAIN, permit.clone().to_xdr(env))
+}
+
+// Vouchers are signed together with the collection they mint into
+pub fn voucher_message(env: &Env, collection_id: u64, voucher: &MintVoucher) -> Bytes {
+    message(
+        env,
+        VOUCHER_DOMAIN,
+        (collection_id, voucher.clone()).to_xdr(env),
+    )
+}
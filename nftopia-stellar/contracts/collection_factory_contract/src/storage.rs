@@ -1,22 +1,85 @@
-use soroban_sdk::{contracttype, Address, Env, Map, String};
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, Map, String, TryFromVal, Val, Vec};
 
 use crate::errors::Error;
 
-// ADD THIS DataKey ENUM
+const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+// Layout version of the structs stored below. Bump it, and teach
+// migration.rs to walk the previous layout forward, whenever a stored struct
+// changes shape.
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+
+// Mint phase whose allowlist root is used when no phases are scheduled
+pub(crate) const DEFAULT_PHASE: u32 = 0;
+
+// Royalties are expressed in basis points and capped at 25%
+pub(crate) const MAX_ROYALTY_BPS: u32 = 2500;
+pub(crate) const BPS_DENOMINATOR: i128 = 10_000;
+pub(crate) const MAX_ROYALTY_RECIPIENTS: u32 = 10;
+
+// Upper bound on the number of IDs returned by a single page of a listing
+pub(crate) const MAX_PAGE_SIZE: u32 = 100;
+
+// Factory-wide keys live in instance storage; everything keyed by a
+// collection or token lives in persistent storage so that the instance entry
+// does not grow with the number of collections and tokens.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
+    // Instance storage
     FactoryConfig,
+    CollectionWasmHash,
+    PendingOwner,
+    FactoryWasmHash,
+    SchemaVersion,
+    MigrationCursor,
+    PaidMintCollections,
+    // Persistent storage
     CollectionInfo(u64),
     NextTokenId(u64),
     TokenOwner(u64, u32),
     TokenMetadata(u64, u32),
     Balance(u64, Address),
-    Approved(u64, u32),
-    ApprovedForAll(u64, Address, Address),
+    TokenApproval(u64, u32),
+    OperatorApproval(u64, Address, Address),
+    ApprovalEpoch(Address),
+    PermitNonce(Address),
+    PermitKey(Address),
+    MinterKey(u64, BytesN<32>),
+    VoucherRedeemed(u64, BytesN<32>),
     RoyaltyInfo(u64),
     WhitelistForMint(u64, Address),
     IsPaused(u64),
+    OwnerToken(u64, Address, u32),
+    OwnerTokenIndex(u64, u32),
+    TokenByIndex(u64, u32),
+    TokenIndex(u64, u32),
+    CreatorCollection(Address, u32),
+    CreatorCollectionCount(Address),
+    PendingCollectionAdmin(u64),
+    RoleMember(u64, Role, Address),
+    RoleMemberAt(u64, Role, u32),
+    RoleMemberCount(u64, Role),
+    MerkleRoot(u64, u32, u32),
+    AllowlistMinted(u64, u32, u32, Address),
+    MetadataFrozen(u64),
+    TokenMetadataFrozen(u64, u32),
+    MintPrice(u64),
+    MintPhases(u64),
+    PhaseSchedule(u64),
+    HasPaidMints(u64),
+    PhaseMinted(u64, u32, u32),
+    PhaseWalletMinted(u64, u32, u32, Address),
+    AttributeSchema(u64),
+    TokenUriConfig(u64),
+    TokenRoyaltyInfo(u64, u32),
+    RoyaltySplits(u64),
+    CollectionContractWasm(u64),
+    MigratedOwnerTokens(u64, Address),
 }
 
 #[contracttype]
@@ -27,6 +90,57 @@ pub enum MetadataSchema {
     Advanced,
 }
 
+// Value types an Advanced collection can require of an attribute. Integers
+// and decimals are base-10 strings with an optional leading '-'; timestamps
+// are unix seconds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AttributeType {
+    Text,
+    Integer,
+    Decimal,
+    Timestamp,
+    Enum(Vec<String>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AttributeDefinition {
+    pub key: String,
+    pub value_type: AttributeType,
+    pub required: bool,
+}
+
+// Who may move tokens once minted. Soulbound tokens can only be minted and
+// burned; AdminTransferOnly lets the collection admin reassign tokens, e.g.
+// to recover a holder's lost key.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TransferPolicy {
+    Transferable,
+    Soulbound,
+    AdminTransferOnly,
+}
+
+// Permissions the collection admin can delegate. The admin itself holds
+// every role implicitly.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Minter,
+    Pauser,
+    RoyaltyAdmin,
+    MetadataEditor,
+    WhitelistManager,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CollectionStatus {
+    Active,
+    Paused,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CollectionConfig {
@@ -41,6 +155,69 @@ pub struct CollectionConfig {
     pub metadata_schema: MetadataSchema,
     pub is_pausable: bool,
     pub is_upgradeable: bool,
+    pub transfer_policy: TransferPolicy,
+}
+
+// Changes requested through update_collection_config; None leaves a field as
+// it is. Name, symbol, royalties and upgradeability have their own paths.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionConfigUpdate {
+    pub description: Option<String>,
+    pub base_uri: Option<String>,
+    pub max_supply: Option<u32>,
+    pub is_public_mint: Option<bool>,
+    pub is_pausable: Option<bool>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintPrice {
+    pub token: Address,
+    pub amount: i128,
+}
+
+// A scheduled mint window, open for start_time <= now < end_time (ledger
+// timestamps). `price` is charged in `price_token` for every token minted.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintPhase {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub price: i128,
+    pub price_token: Option<Address>,
+    pub wallet_limit: Option<u32>,
+    pub supply_cap: Option<u32>,
+    pub requires_allowlist: bool,
+}
+
+// Opts a collection into derived token URIs: base_uri + token id + suffix.
+// While placeholder_uri is set and the collection is unrevealed, every token
+// resolves to the placeholder instead. provenance_hash commits to the final
+// metadata before minting starts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenUriConfig {
+    pub suffix: String,
+    pub placeholder_uri: Option<String>,
+    pub provenance_hash: Option<BytesN<32>>,
+    pub revealed: bool,
+}
+
+// A creator-signed offer to mint one token to whoever buys it. `signer` is
+// the collection minter key that signed it, `voucher_id` can be redeemed only
+// once per collection, and `expires_at` is a ledger timestamp. `price` is
+// charged in `price_token`; a price of 0 mints for free.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintVoucher {
+    pub voucher_id: BytesN<32>,
+    pub signer: BytesN<32>,
+    pub uri: String,
+    pub attributes: Map<String, String>,
+    pub price: i128,
+    pub price_token: Option<Address>,
+    pub expires_at: u64,
 }
 
 #[contracttype]
@@ -59,6 +236,7 @@ pub struct TokenMetadata {
 pub struct CollectionInfo {
     pub address: Address,
     pub creator: Address,
+    pub admin: Address,
     pub config: CollectionConfig,
     pub created_at: u64,
     pub total_tokens: u32,
@@ -69,14 +247,66 @@ pub struct CollectionInfo {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RoyaltyInfo {
     pub recipient: Address,
+    // Basis points of the sale price
     pub percentage: u32,
 }
 
+// One recipient's part of a collection royalty, in basis points of the sale
+// price. A collection's shares sum to its royalty percentage.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltyShare {
+    pub recipient: Address,
+    pub share: u32,
+}
+
+// A spender approved for one token until `live_until_ledger`. `epoch` is the
+// owner's approval epoch when it was granted; revoking all of an owner's
+// approvals moves the epoch on, which voids every earlier grant.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenApproval {
+    pub approved: Address,
+    pub live_until_ledger: u32,
+    pub epoch: u32,
+}
+
+// An operator approved for all of an owner's tokens in a collection, expiring
+// and revoked in bulk the same way as a TokenApproval.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorApproval {
+    pub live_until_ledger: u32,
+    pub epoch: u32,
+}
+
+// Where a batched migration stopped: the collection being walked, the next
+// token in it (0 while its CollectionInfo is still to be rewritten) and how
+// many live tokens have been indexed so far.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationCursor {
+    pub collection_id: u64,
+    pub token_id: u32,
+    pub indexed: u32,
+}
+
+// An offer to hand over the factory ownership or a collection's admin role,
+// valid until `expiration_ledger` and completed when `new_admin` accepts it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingTransfer {
+    pub new_admin: Address,
+    pub expiration_ledger: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FactoryConfig {
     pub owner: Address,
     pub factory_fee: i128,
+    pub fee_token: Option<Address>,
+    pub platform_fee_bps: u32,
     pub max_collections: Option<u32>,
     pub total_collections: u32,
     pub accumulated_fees: i128,
@@ -84,16 +314,66 @@ pub struct FactoryConfig {
 }
 
 pub trait Storage {
+    fn has_factory_config(env: &Env) -> bool;
     fn get_factory_config(env: &Env) -> Result<FactoryConfig, Error>;
     fn set_factory_config(env: &Env, config: &FactoryConfig);
 
+    fn get_schema_version(env: &Env) -> u32;
+    fn set_schema_version(env: &Env, version: u32);
+
+    fn get_migration_cursor(env: &Env) -> Option<MigrationCursor>;
+    fn set_migration_cursor(env: &Env, cursor: &MigrationCursor);
+    fn remove_migration_cursor(env: &Env);
+
+    fn get_migrated_owner_tokens(env: &Env, collection_id: u64, owner: &Address) -> u32;
+    fn set_migrated_owner_tokens(env: &Env, collection_id: u64, owner: &Address, count: u32);
+    fn remove_migrated_owner_tokens(env: &Env, collection_id: u64, owner: &Address);
+
+    fn get_collection_wasm_hash(env: &Env) -> Option<BytesN<32>>;
+    fn set_collection_wasm_hash(env: &Env, wasm_hash: &BytesN<32>);
+
+    fn get_factory_wasm_hash(env: &Env) -> Option<BytesN<32>>;
+    fn set_factory_wasm_hash(env: &Env, wasm_hash: &BytesN<32>);
+
+    fn get_collection_contract_wasm(env: &Env, collection_id: u64) -> Option<BytesN<32>>;
+    fn set_collection_contract_wasm(env: &Env, collection_id: u64, wasm_hash: &BytesN<32>);
+
+    fn get_pending_owner(env: &Env) -> Option<PendingTransfer>;
+    fn set_pending_owner(env: &Env, pending: &PendingTransfer);
+    fn remove_pending_owner(env: &Env);
+
     fn get_collection_info(env: &Env, collection_id: u64) -> Result<CollectionInfo, Error>;
     fn set_collection_info(env: &Env, collection_id: u64, info: &CollectionInfo);
 
-    #[allow(dead_code)]
-    fn get_collections_count(env: &Env) -> u32;
-    #[allow(dead_code)]
-    fn increment_collections_count(env: &Env);
+    fn get_pending_collection_admin(env: &Env, collection_id: u64) -> Option<PendingTransfer>;
+    fn set_pending_collection_admin(env: &Env, collection_id: u64, pending: &PendingTransfer);
+    fn remove_pending_collection_admin(env: &Env, collection_id: u64);
+
+    fn get_role_member_index(
+        env: &Env,
+        collection_id: u64,
+        role: Role,
+        account: &Address,
+    ) -> Option<u32>;
+    fn set_role_member_index(
+        env: &Env,
+        collection_id: u64,
+        role: Role,
+        account: &Address,
+        index: u32,
+    );
+    fn remove_role_member_index(env: &Env, collection_id: u64, role: Role, account: &Address);
+
+    fn get_role_member(env: &Env, collection_id: u64, role: Role, index: u32) -> Option<Address>;
+    fn set_role_member(env: &Env, collection_id: u64, role: Role, index: u32, account: &Address);
+    fn remove_role_member(env: &Env, collection_id: u64, role: Role, index: u32);
+
+    fn get_role_member_count(env: &Env, collection_id: u64, role: Role) -> u32;
+    fn set_role_member_count(env: &Env, collection_id: u64, role: Role, count: u32);
+
+    fn get_creator_collection(env: &Env, creator: &Address, index: u32) -> Option<u64>;
+    fn add_creator_collection(env: &Env, creator: &Address, collection_id: u64);
+    fn get_creator_collection_count(env: &Env, creator: &Address) -> u32;
 
     fn get_next_token_id(env: &Env, collection_id: u64) -> u32;
     fn set_next_token_id(env: &Env, collection_id: u64, next_id: u32);
@@ -106,32 +386,62 @@ pub trait Storage {
     fn get_token_metadata(env: &Env, collection_id: u64, token_id: u32) -> Option<TokenMetadata>;
     fn set_token_metadata(env: &Env, collection_id: u64, token_id: u32, metadata: &TokenMetadata);
 
+    fn is_metadata_frozen(env: &Env, collection_id: u64) -> bool;
+    fn set_metadata_frozen(env: &Env, collection_id: u64);
+    fn is_token_metadata_frozen(env: &Env, collection_id: u64, token_id: u32) -> bool;
+    fn set_token_metadata_frozen(env: &Env, collection_id: u64, token_id: u32);
+
     fn get_balance(env: &Env, collection_id: u64, address: &Address) -> u32;
     fn set_balance(env: &Env, collection_id: u64, address: &Address, balance: u32);
     fn increment_balance(env: &Env, collection_id: u64, address: &Address);
     fn decrement_balance(env: &Env, collection_id: u64, address: &Address);
 
-    fn get_approved(env: &Env, collection_id: u64, token_id: u32) -> Option<Address>;
-    fn set_approved(env: &Env, collection_id: u64, token_id: u32, approved: &Address);
-    fn remove_approved(env: &Env, collection_id: u64, token_id: u32);
+    fn get_token_approval(env: &Env, collection_id: u64, token_id: u32) -> Option<TokenApproval>;
+    fn set_token_approval(env: &Env, collection_id: u64, token_id: u32, approval: &TokenApproval);
+    fn remove_token_approval(env: &Env, collection_id: u64, token_id: u32);
 
-    fn get_approved_for_all(
+    fn get_operator_approval(
         env: &Env,
         collection_id: u64,
         owner: &Address,
         operator: &Address,
-    ) -> bool;
-    fn set_approved_for_all(
+    ) -> Option<OperatorApproval>;
+    fn set_operator_approval(
         env: &Env,
         collection_id: u64,
         owner: &Address,
         operator: &Address,
-        approved: bool,
+        approval: &OperatorApproval,
     );
+    fn remove_operator_approval(env: &Env, collection_id: u64, owner: &Address, operator: &Address);
+
+    fn get_approval_epoch(env: &Env, owner: &Address) -> u32;
+    fn set_approval_epoch(env: &Env, owner: &Address, epoch: u32);
+
+    fn get_permit_nonce(env: &Env, owner: &Address) -> u64;
+    fn set_permit_nonce(env: &Env, owner: &Address, nonce: u64);
+    fn get_permit_key(env: &Env, owner: &Address) -> Option<BytesN<32>>;
+    fn set_permit_key(env: &Env, owner: &Address, public_key: &BytesN<32>);
+    fn remove_permit_key(env: &Env, owner: &Address);
+
+    fn is_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>) -> bool;
+    fn set_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>);
+    fn remove_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>);
+
+    fn is_voucher_redeemed(env: &Env, collection_id: u64, voucher_id: &BytesN<32>) -> bool;
+    fn set_voucher_redeemed(env: &Env, collection_id: u64, voucher_id: &BytesN<32>);
 
     fn get_royalty_info(env: &Env, collection_id: u64) -> Option<RoyaltyInfo>;
     fn set_royalty_info(env: &Env, collection_id: u64, royalty: &RoyaltyInfo);
 
+    fn get_royalty_splits(env: &Env, collection_id: u64) -> Vec<RoyaltyShare>;
+    fn set_royalty_splits(env: &Env, collection_id: u64, shares: &Vec<RoyaltyShare>);
+    fn remove_royalty_splits(env: &Env, collection_id: u64);
+
+    fn get_token_royalty_info(env: &Env, collection_id: u64, token_id: u32) -> Option<RoyaltyInfo>;
+    fn set_token_royalty_info(env: &Env, collection_id: u64, token_id: u32, royalty: &RoyaltyInfo);
+    fn remove_token_royalty_info(env: &Env, collection_id: u64, token_id: u32);
+
     fn is_whitelisted_for_mint(env: &Env, collection_id: u64, address: &Address) -> bool;
     fn set_whitelisted_for_mint(
         env: &Env,
@@ -140,61 +450,295 @@ pub trait Storage {
         whitelisted: bool,
     );
 
+    fn get_mint_price(env: &Env, collection_id: u64) -> Option<MintPrice>;
+    fn set_mint_price(env: &Env, collection_id: u64, price: &MintPrice);
+    fn remove_mint_price(env: &Env, collection_id: u64);
+
+    fn get_mint_phases(env: &Env, collection_id: u64) -> Vec<MintPhase>;
+    fn set_mint_phases(env: &Env, collection_id: u64, phases: &Vec<MintPhase>);
+
+    fn has_paid_mints(env: &Env, collection_id: u64) -> bool;
+    fn set_has_paid_mints(env: &Env, collection_id: u64, paid: bool);
+    fn get_paid_mint_collections(env: &Env) -> u32;
+    fn set_paid_mint_collections(env: &Env, count: u32);
+
+    fn get_attribute_schema(env: &Env, collection_id: u64) -> Vec<AttributeDefinition>;
+    fn set_attribute_schema(env: &Env, collection_id: u64, schema: &Vec<AttributeDefinition>);
+
+    fn get_token_uri_config(env: &Env, collection_id: u64) -> Option<TokenUriConfig>;
+    fn set_token_uri_config(env: &Env, collection_id: u64, config: &TokenUriConfig);
+
+    // Phase counters and allowlist roots belong to the schedule they were
+    // recorded under
+    fn get_phase_schedule(env: &Env, collection_id: u64) -> u32;
+    fn set_phase_schedule(env: &Env, collection_id: u64, schedule: u32);
+
+    fn get_phase_minted(env: &Env, collection_id: u64, phase: u32) -> u32;
+    fn set_phase_minted(env: &Env, collection_id: u64, phase: u32, minted: u32);
+
+    fn get_phase_wallet_minted(env: &Env, collection_id: u64, phase: u32, address: &Address)
+        -> u32;
+    fn set_phase_wallet_minted(
+        env: &Env,
+        collection_id: u64,
+        phase: u32,
+        address: &Address,
+        minted: u32,
+    );
+
+    fn get_merkle_root(env: &Env, collection_id: u64, phase: u32) -> Option<BytesN<32>>;
+    fn set_merkle_root(env: &Env, collection_id: u64, phase: u32, root: &BytesN<32>);
+
+    fn get_allowlist_minted(env: &Env, collection_id: u64, phase: u32, address: &Address) -> u32;
+    fn set_allowlist_minted(
+        env: &Env,
+        collection_id: u64,
+        phase: u32,
+        address: &Address,
+        minted: u32,
+    );
+
     fn is_collection_paused(env: &Env, collection_id: u64) -> bool;
     fn set_collection_paused(env: &Env, collection_id: u64, paused: bool);
+
+    // Enumeration: each owner's tokens and all tokens of a collection are kept
+    // as dense index -> token_id lists, with a reverse token_id -> index entry
+    fn get_owner_token(env: &Env, collection_id: u64, owner: &Address, index: u32) -> Option<u32>;
+    fn set_owner_token(env: &Env, collection_id: u64, owner: &Address, index: u32, token_id: u32);
+    fn remove_owner_token(env: &Env, collection_id: u64, owner: &Address, index: u32);
+
+    fn get_owner_token_index(env: &Env, collection_id: u64, token_id: u32) -> Option<u32>;
+    fn set_owner_token_index(env: &Env, collection_id: u64, token_id: u32, index: u32);
+    fn remove_owner_token_index(env: &Env, collection_id: u64, token_id: u32);
+
+    fn get_token_by_index(env: &Env, collection_id: u64, index: u32) -> Option<u32>;
+    fn set_token_by_index(env: &Env, collection_id: u64, index: u32, token_id: u32);
+    fn remove_token_by_index(env: &Env, collection_id: u64, index: u32);
+
+    fn get_token_index(env: &Env, collection_id: u64, token_id: u32) -> Option<u32>;
+    fn set_token_index(env: &Env, collection_id: u64, token_id: u32, index: u32);
+    fn remove_token_index(env: &Env, collection_id: u64, token_id: u32);
 }
 
 impl Storage for DataKey {
+    fn has_factory_config(env: &Env) -> bool {
+        env.storage().instance().has(&DataKey::FactoryConfig)
+    }
+
     fn get_factory_config(env: &Env) -> Result<FactoryConfig, Error> {
-        env.storage()
-            .instance()
-            .get(&DataKey::FactoryConfig)
-            .ok_or(Error::StorageError)
+        require_current_schema(env)?;
+        read_instance(env, &DataKey::FactoryConfig).ok_or(Error::StorageError)
     }
 
     fn set_factory_config(env: &Env, config: &FactoryConfig) {
+        write_instance(env, &DataKey::FactoryConfig, config);
+    }
+
+    // Factories initialized before versioning have a config but no version
+    fn get_schema_version(env: &Env) -> u32 {
+        read_instance(env, &DataKey::SchemaVersion).unwrap_or_else(|| {
+            if Self::has_factory_config(env) {
+                1
+            } else {
+                SCHEMA_VERSION
+            }
+        })
+    }
+
+    fn set_schema_version(env: &Env, version: u32) {
+        write_instance(env, &DataKey::SchemaVersion, &version);
+    }
+
+    fn get_migration_cursor(env: &Env) -> Option<MigrationCursor> {
+        read_instance(env, &DataKey::MigrationCursor)
+    }
+
+    fn set_migration_cursor(env: &Env, cursor: &MigrationCursor) {
+        write_instance(env, &DataKey::MigrationCursor, cursor);
+    }
+
+    fn remove_migration_cursor(env: &Env) {
+        env.storage().instance().remove(&DataKey::MigrationCursor);
+    }
+
+    fn get_migrated_owner_tokens(env: &Env, collection_id: u64, owner: &Address) -> u32 {
+        read_persistent(
+            env,
+            &DataKey::MigratedOwnerTokens(collection_id, owner.clone()),
+        )
+        .unwrap_or(0)
+    }
+
+    fn set_migrated_owner_tokens(env: &Env, collection_id: u64, owner: &Address, count: u32) {
+        write_persistent(
+            env,
+            &DataKey::MigratedOwnerTokens(collection_id, owner.clone()),
+            &count,
+        );
+    }
+
+    fn remove_migrated_owner_tokens(env: &Env, collection_id: u64, owner: &Address) {
         env.storage()
-            .instance()
-            .set(&DataKey::FactoryConfig, config);
+            .persistent()
+            .remove(&DataKey::MigratedOwnerTokens(collection_id, owner.clone()));
+    }
+
+    fn get_collection_wasm_hash(env: &Env) -> Option<BytesN<32>> {
+        read_instance(env, &DataKey::CollectionWasmHash)
+    }
+
+    fn set_collection_wasm_hash(env: &Env, wasm_hash: &BytesN<32>) {
+        write_instance(env, &DataKey::CollectionWasmHash, wasm_hash);
+    }
+
+    fn get_factory_wasm_hash(env: &Env) -> Option<BytesN<32>> {
+        read_instance(env, &DataKey::FactoryWasmHash)
+    }
+
+    fn set_factory_wasm_hash(env: &Env, wasm_hash: &BytesN<32>) {
+        write_instance(env, &DataKey::FactoryWasmHash, wasm_hash);
+    }
+
+    fn get_collection_contract_wasm(env: &Env, collection_id: u64) -> Option<BytesN<32>> {
+        read_persistent(env, &DataKey::CollectionContractWasm(collection_id))
+    }
+
+    fn set_collection_contract_wasm(env: &Env, collection_id: u64, wasm_hash: &BytesN<32>) {
+        write_persistent(
+            env,
+            &DataKey::CollectionContractWasm(collection_id),
+            wasm_hash,
+        );
+    }
+
+    fn get_pending_owner(env: &Env) -> Option<PendingTransfer> {
+        read_instance(env, &DataKey::PendingOwner)
+    }
+
+    fn set_pending_owner(env: &Env, pending: &PendingTransfer) {
+        write_instance(env, &DataKey::PendingOwner, pending);
+    }
+
+    fn remove_pending_owner(env: &Env) {
+        env.storage().instance().remove(&DataKey::PendingOwner);
     }
 
     fn get_collection_info(env: &Env, collection_id: u64) -> Result<CollectionInfo, Error> {
-        env.storage()
-            .instance()
-            .get(&DataKey::CollectionInfo(collection_id))
+        require_current_schema(env)?;
+        read_persistent(env, &DataKey::CollectionInfo(collection_id))
             .ok_or(Error::CollectionNotFound)
     }
 
     fn set_collection_info(env: &Env, collection_id: u64, info: &CollectionInfo) {
+        write_persistent(env, &DataKey::CollectionInfo(collection_id), info);
+    }
+
+    fn get_pending_collection_admin(env: &Env, collection_id: u64) -> Option<PendingTransfer> {
+        read_persistent(env, &DataKey::PendingCollectionAdmin(collection_id))
+    }
+
+    fn set_pending_collection_admin(env: &Env, collection_id: u64, pending: &PendingTransfer) {
+        write_persistent(
+            env,
+            &DataKey::PendingCollectionAdmin(collection_id),
+            pending,
+        );
+    }
+
+    fn remove_pending_collection_admin(env: &Env, collection_id: u64) {
         env.storage()
-            .instance()
-            .set(&DataKey::CollectionInfo(collection_id), info);
+            .persistent()
+            .remove(&DataKey::PendingCollectionAdmin(collection_id));
     }
 
-    fn get_collections_count(env: &Env) -> u32 {
-        Self::get_factory_config(env)
-            .map(|config| config.total_collections)
-            .unwrap_or(0)
+    fn get_role_member_index(
+        env: &Env,
+        collection_id: u64,
+        role: Role,
+        account: &Address,
+    ) -> Option<u32> {
+        read_persistent(
+            env,
+            &DataKey::RoleMember(collection_id, role, account.clone()),
+        )
     }
 
-    fn increment_collections_count(env: &Env) {
-        if let Ok(mut config) = Self::get_factory_config(env) {
-            config.total_collections += 1;
-            Self::set_factory_config(env, &config);
-        }
+    fn set_role_member_index(
+        env: &Env,
+        collection_id: u64,
+        role: Role,
+        account: &Address,
+        index: u32,
+    ) {
+        write_persistent(
+            env,
+            &DataKey::RoleMember(collection_id, role, account.clone()),
+            &index,
+        );
     }
 
-    fn get_next_token_id(env: &Env, collection_id: u64) -> u32 {
+    fn remove_role_member_index(env: &Env, collection_id: u64, role: Role, account: &Address) {
+        env.storage().persistent().remove(&DataKey::RoleMember(
+            collection_id,
+            role,
+            account.clone(),
+        ));
+    }
+
+    fn get_role_member(env: &Env, collection_id: u64, role: Role, index: u32) -> Option<Address> {
+        read_persistent(env, &DataKey::RoleMemberAt(collection_id, role, index))
+    }
+
+    fn set_role_member(env: &Env, collection_id: u64, role: Role, index: u32, account: &Address) {
+        write_persistent(
+            env,
+            &DataKey::RoleMemberAt(collection_id, role, index),
+            account,
+        );
+    }
+
+    fn remove_role_member(env: &Env, collection_id: u64, role: Role, index: u32) {
         env.storage()
-            .instance()
-            .get(&DataKey::NextTokenId(collection_id))
-            .unwrap_or(1) // Start from token ID 1
+            .persistent()
+            .remove(&DataKey::RoleMemberAt(collection_id, role, index));
+    }
+
+    fn get_role_member_count(env: &Env, collection_id: u64, role: Role) -> u32 {
+        read_persistent(env, &DataKey::RoleMemberCount(collection_id, role)).unwrap_or(0)
+    }
+
+    fn set_role_member_count(env: &Env, collection_id: u64, role: Role, count: u32) {
+        write_persistent(env, &DataKey::RoleMemberCount(collection_id, role), &count);
+    }
+
+    fn get_creator_collection(env: &Env, creator: &Address, index: u32) -> Option<u64> {
+        read_persistent(env, &DataKey::CreatorCollection(creator.clone(), index))
+    }
+
+    fn add_creator_collection(env: &Env, creator: &Address, collection_id: u64) {
+        let count = Self::get_creator_collection_count(env, creator);
+        write_persistent(
+            env,
+            &DataKey::CreatorCollection(creator.clone(), count),
+            &collection_id,
+        );
+        write_persistent(
+            env,
+            &DataKey::CreatorCollectionCount(creator.clone()),
+            &(count + 1),
+        );
+    }
+
+    fn get_creator_collection_count(env: &Env, creator: &Address) -> u32 {
+        read_persistent(env, &DataKey::CreatorCollectionCount(creator.clone())).unwrap_or(0)
+    }
+
+    fn get_next_token_id(env: &Env, collection_id: u64) -> u32 {
+        read_persistent(env, &DataKey::NextTokenId(collection_id)).unwrap_or(1) // Start from token ID 1
     }
 
     fn set_next_token_id(env: &Env, collection_id: u64, next_id: u32) {
-        env.storage()
-            .instance()
-            .set(&DataKey::NextTokenId(collection_id), &next_id);
+        write_persistent(env, &DataKey::NextTokenId(collection_id), &next_id);
     }
 
     fn increment_token_id(env: &Env, collection_id: u64) {
@@ -203,46 +747,62 @@ impl Storage for DataKey {
     }
 
     fn get_token_owner(env: &Env, collection_id: u64, token_id: u32) -> Option<Address> {
-        env.storage()
-            .instance()
-            .get(&DataKey::TokenOwner(collection_id, token_id))
+        read_persistent(env, &DataKey::TokenOwner(collection_id, token_id))
     }
 
     fn set_token_owner(env: &Env, collection_id: u64, token_id: u32, owner: &Address) {
-        env.storage()
-            .instance()
-            .set(&DataKey::TokenOwner(collection_id, token_id), owner);
+        write_persistent(env, &DataKey::TokenOwner(collection_id, token_id), owner);
     }
 
     fn remove_token_owner(env: &Env, collection_id: u64, token_id: u32) {
         env.storage()
-            .instance()
+            .persistent()
             .remove(&DataKey::TokenOwner(collection_id, token_id));
     }
 
     fn get_token_metadata(env: &Env, collection_id: u64, token_id: u32) -> Option<TokenMetadata> {
-        env.storage()
-            .instance()
-            .get(&DataKey::TokenMetadata(collection_id, token_id))
+        read_persistent(env, &DataKey::TokenMetadata(collection_id, token_id))
     }
 
     fn set_token_metadata(env: &Env, collection_id: u64, token_id: u32, metadata: &TokenMetadata) {
-        env.storage()
-            .instance()
-            .set(&DataKey::TokenMetadata(collection_id, token_id), metadata);
+        write_persistent(
+            env,
+            &DataKey::TokenMetadata(collection_id, token_id),
+            metadata,
+        );
+    }
+
+    fn is_metadata_frozen(env: &Env, collection_id: u64) -> bool {
+        read_persistent(env, &DataKey::MetadataFrozen(collection_id)).unwrap_or(false)
+    }
+
+    fn set_metadata_frozen(env: &Env, collection_id: u64) {
+        write_persistent(env, &DataKey::MetadataFrozen(collection_id), &true);
+    }
+
+    fn is_token_metadata_frozen(env: &Env, collection_id: u64, token_id: u32) -> bool {
+        read_persistent(env, &DataKey::TokenMetadataFrozen(collection_id, token_id))
+            .unwrap_or(false)
+    }
+
+    fn set_token_metadata_frozen(env: &Env, collection_id: u64, token_id: u32) {
+        write_persistent(
+            env,
+            &DataKey::TokenMetadataFrozen(collection_id, token_id),
+            &true,
+        );
     }
 
     fn get_balance(env: &Env, collection_id: u64, address: &Address) -> u32 {
-        env.storage()
-            .instance()
-            .get(&DataKey::Balance(collection_id, address.clone()))
-            .unwrap_or(0)
+        read_persistent(env, &DataKey::Balance(collection_id, address.clone())).unwrap_or(0)
     }
 
     fn set_balance(env: &Env, collection_id: u64, address: &Address, balance: u32) {
-        env.storage()
-            .instance()
-            .set(&DataKey::Balance(collection_id, address.clone()), &balance);
+        write_persistent(
+            env,
+            &DataKey::Balance(collection_id, address.clone()),
+            &balance,
+        );
     }
 
     fn increment_balance(env: &Env, collection_id: u64, address: &Address) {
@@ -257,70 +817,177 @@ impl Storage for DataKey {
         }
     }
 
-    fn get_approved(env: &Env, collection_id: u64, token_id: u32) -> Option<Address> {
-        env.storage()
-            .instance()
-            .get(&DataKey::Approved(collection_id, token_id))
+    fn get_token_approval(env: &Env, collection_id: u64, token_id: u32) -> Option<TokenApproval> {
+        read_persistent(env, &DataKey::TokenApproval(collection_id, token_id))
     }
 
-    fn set_approved(env: &Env, collection_id: u64, token_id: u32, approved: &Address) {
-        env.storage()
-            .instance()
-            .set(&DataKey::Approved(collection_id, token_id), approved);
+    fn set_token_approval(env: &Env, collection_id: u64, token_id: u32, approval: &TokenApproval) {
+        write_persistent(
+            env,
+            &DataKey::TokenApproval(collection_id, token_id),
+            approval,
+        );
     }
 
-    fn remove_approved(env: &Env, collection_id: u64, token_id: u32) {
+    fn remove_token_approval(env: &Env, collection_id: u64, token_id: u32) {
         env.storage()
-            .instance()
-            .remove(&DataKey::Approved(collection_id, token_id));
+            .persistent()
+            .remove(&DataKey::TokenApproval(collection_id, token_id));
     }
 
-    fn get_approved_for_all(
+    fn get_operator_approval(
         env: &Env,
         collection_id: u64,
         owner: &Address,
         operator: &Address,
-    ) -> bool {
-        env.storage()
-            .instance()
-            .get(&DataKey::ApprovedForAll(
-                collection_id,
-                owner.clone(),
-                operator.clone(),
-            ))
-            .unwrap_or(false)
+    ) -> Option<OperatorApproval> {
+        read_persistent(
+            env,
+            &DataKey::OperatorApproval(collection_id, owner.clone(), operator.clone()),
+        )
     }
 
-    fn set_approved_for_all(
+    fn set_operator_approval(
         env: &Env,
         collection_id: u64,
         owner: &Address,
         operator: &Address,
-        approved: bool,
+        approval: &OperatorApproval,
     ) {
-        env.storage().instance().set(
-            &DataKey::ApprovedForAll(collection_id, owner.clone(), operator.clone()),
-            &approved,
+        write_persistent(
+            env,
+            &DataKey::OperatorApproval(collection_id, owner.clone(), operator.clone()),
+            approval,
         );
     }
 
-    fn get_royalty_info(env: &Env, collection_id: u64) -> Option<RoyaltyInfo> {
+    fn remove_operator_approval(
+        env: &Env,
+        collection_id: u64,
+        owner: &Address,
+        operator: &Address,
+    ) {
         env.storage()
-            .instance()
-            .get(&DataKey::RoyaltyInfo(collection_id))
+            .persistent()
+            .remove(&DataKey::OperatorApproval(
+                collection_id,
+                owner.clone(),
+                operator.clone(),
+            ));
+    }
+
+    fn get_approval_epoch(env: &Env, owner: &Address) -> u32 {
+        read_persistent(env, &DataKey::ApprovalEpoch(owner.clone())).unwrap_or(0)
+    }
+
+    fn set_approval_epoch(env: &Env, owner: &Address, epoch: u32) {
+        write_persistent(env, &DataKey::ApprovalEpoch(owner.clone()), &epoch);
+    }
+
+    fn get_permit_nonce(env: &Env, owner: &Address) -> u64 {
+        read_persistent(env, &DataKey::PermitNonce(owner.clone())).unwrap_or(0)
+    }
+
+    fn set_permit_nonce(env: &Env, owner: &Address, nonce: u64) {
+        write_persistent(env, &DataKey::PermitNonce(owner.clone()), &nonce);
+    }
+
+    fn get_permit_key(env: &Env, owner: &Address) -> Option<BytesN<32>> {
+        read_persistent(env, &DataKey::PermitKey(owner.clone()))
+    }
+
+    fn set_permit_key(env: &Env, owner: &Address, public_key: &BytesN<32>) {
+        write_persistent(env, &DataKey::PermitKey(owner.clone()), public_key);
+    }
+
+    fn remove_permit_key(env: &Env, owner: &Address) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PermitKey(owner.clone()));
+    }
+
+    fn is_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>) -> bool {
+        read_persistent(env, &DataKey::MinterKey(collection_id, public_key.clone()))
+            .unwrap_or(false)
+    }
+
+    fn set_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>) {
+        write_persistent(
+            env,
+            &DataKey::MinterKey(collection_id, public_key.clone()),
+            &true,
+        );
+    }
+
+    fn remove_minter_key(env: &Env, collection_id: u64, public_key: &BytesN<32>) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::MinterKey(collection_id, public_key.clone()));
+    }
+
+    fn is_voucher_redeemed(env: &Env, collection_id: u64, voucher_id: &BytesN<32>) -> bool {
+        read_persistent(
+            env,
+            &DataKey::VoucherRedeemed(collection_id, voucher_id.clone()),
+        )
+        .unwrap_or(false)
+    }
+
+    fn set_voucher_redeemed(env: &Env, collection_id: u64, voucher_id: &BytesN<32>) {
+        write_persistent(
+            env,
+            &DataKey::VoucherRedeemed(collection_id, voucher_id.clone()),
+            &true,
+        );
+    }
+
+    fn get_royalty_info(env: &Env, collection_id: u64) -> Option<RoyaltyInfo> {
+        read_persistent(env, &DataKey::RoyaltyInfo(collection_id))
     }
 
     fn set_royalty_info(env: &Env, collection_id: u64, royalty: &RoyaltyInfo) {
+        write_persistent(env, &DataKey::RoyaltyInfo(collection_id), royalty);
+    }
+
+    fn get_royalty_splits(env: &Env, collection_id: u64) -> Vec<RoyaltyShare> {
+        read_persistent(env, &DataKey::RoyaltySplits(collection_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_royalty_splits(env: &Env, collection_id: u64, shares: &Vec<RoyaltyShare>) {
+        write_persistent(env, &DataKey::RoyaltySplits(collection_id), shares);
+    }
+
+    fn remove_royalty_splits(env: &Env, collection_id: u64) {
         env.storage()
-            .instance()
-            .set(&DataKey::RoyaltyInfo(collection_id), royalty);
+            .persistent()
+            .remove(&DataKey::RoyaltySplits(collection_id));
     }
 
-    fn is_whitelisted_for_mint(env: &Env, collection_id: u64, address: &Address) -> bool {
+    fn get_token_royalty_info(env: &Env, collection_id: u64, token_id: u32) -> Option<RoyaltyInfo> {
+        read_persistent(env, &DataKey::TokenRoyaltyInfo(collection_id, token_id))
+    }
+
+    fn set_token_royalty_info(env: &Env, collection_id: u64, token_id: u32, royalty: &RoyaltyInfo) {
+        write_persistent(
+            env,
+            &DataKey::TokenRoyaltyInfo(collection_id, token_id),
+            royalty,
+        );
+    }
+
+    fn remove_token_royalty_info(env: &Env, collection_id: u64, token_id: u32) {
         env.storage()
-            .instance()
-            .get(&DataKey::WhitelistForMint(collection_id, address.clone()))
-            .unwrap_or(false)
+            .persistent()
+            .remove(&DataKey::TokenRoyaltyInfo(collection_id, token_id));
+    }
+
+    fn is_whitelisted_for_mint(env: &Env, collection_id: u64, address: &Address) -> bool {
+        read_persistent(
+            env,
+            &DataKey::WhitelistForMint(collection_id, address.clone()),
+        )
+        .unwrap_or(false)
     }
 
     fn set_whitelisted_for_mint(
@@ -329,22 +996,277 @@ impl Storage for DataKey {
         address: &Address,
         whitelisted: bool,
     ) {
-        env.storage().instance().set(
+        write_persistent(
+            env,
             &DataKey::WhitelistForMint(collection_id, address.clone()),
             &whitelisted,
         );
     }
 
-    fn is_collection_paused(env: &Env, collection_id: u64) -> bool {
+    fn get_mint_price(env: &Env, collection_id: u64) -> Option<MintPrice> {
+        read_persistent(env, &DataKey::MintPrice(collection_id))
+    }
+
+    fn set_mint_price(env: &Env, collection_id: u64, price: &MintPrice) {
+        write_persistent(env, &DataKey::MintPrice(collection_id), price);
+    }
+
+    fn remove_mint_price(env: &Env, collection_id: u64) {
         env.storage()
-            .instance()
-            .get(&DataKey::IsPaused(collection_id))
-            .unwrap_or(false)
+            .persistent()
+            .remove(&DataKey::MintPrice(collection_id));
+    }
+
+    fn get_mint_phases(env: &Env, collection_id: u64) -> Vec<MintPhase> {
+        read_persistent(env, &DataKey::MintPhases(collection_id)).unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_mint_phases(env: &Env, collection_id: u64, phases: &Vec<MintPhase>) {
+        write_persistent(env, &DataKey::MintPhases(collection_id), phases);
+    }
+
+    fn has_paid_mints(env: &Env, collection_id: u64) -> bool {
+        read_persistent(env, &DataKey::HasPaidMints(collection_id)).unwrap_or(false)
+    }
+
+    fn set_has_paid_mints(env: &Env, collection_id: u64, paid: bool) {
+        if paid {
+            write_persistent(env, &DataKey::HasPaidMints(collection_id), &true);
+        } else {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::HasPaidMints(collection_id));
+        }
+    }
+
+    fn get_paid_mint_collections(env: &Env) -> u32 {
+        read_instance(env, &DataKey::PaidMintCollections).unwrap_or(0)
+    }
+
+    fn set_paid_mint_collections(env: &Env, count: u32) {
+        write_instance(env, &DataKey::PaidMintCollections, &count);
+    }
+
+    fn get_attribute_schema(env: &Env, collection_id: u64) -> Vec<AttributeDefinition> {
+        read_persistent(env, &DataKey::AttributeSchema(collection_id))
+            .unwrap_or_else(|| Vec::new(env))
+    }
+
+    fn set_attribute_schema(env: &Env, collection_id: u64, schema: &Vec<AttributeDefinition>) {
+        write_persistent(env, &DataKey::AttributeSchema(collection_id), schema);
+    }
+
+    fn get_token_uri_config(env: &Env, collection_id: u64) -> Option<TokenUriConfig> {
+        read_persistent(env, &DataKey::TokenUriConfig(collection_id))
+    }
+
+    fn set_token_uri_config(env: &Env, collection_id: u64, config: &TokenUriConfig) {
+        write_persistent(env, &DataKey::TokenUriConfig(collection_id), config);
+    }
+
+    fn get_phase_schedule(env: &Env, collection_id: u64) -> u32 {
+        read_persistent(env, &DataKey::PhaseSchedule(collection_id)).unwrap_or(0)
+    }
+
+    fn set_phase_schedule(env: &Env, collection_id: u64, schedule: u32) {
+        write_persistent(env, &DataKey::PhaseSchedule(collection_id), &schedule);
+    }
+
+    fn get_phase_minted(env: &Env, collection_id: u64, phase: u32) -> u32 {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        read_persistent(env, &DataKey::PhaseMinted(collection_id, schedule, phase)).unwrap_or(0)
+    }
+
+    fn set_phase_minted(env: &Env, collection_id: u64, phase: u32, minted: u32) {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        write_persistent(
+            env,
+            &DataKey::PhaseMinted(collection_id, schedule, phase),
+            &minted,
+        );
+    }
+
+    fn get_phase_wallet_minted(
+        env: &Env,
+        collection_id: u64,
+        phase: u32,
+        address: &Address,
+    ) -> u32 {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        read_persistent(
+            env,
+            &DataKey::PhaseWalletMinted(collection_id, schedule, phase, address.clone()),
+        )
+        .unwrap_or(0)
+    }
+
+    fn set_phase_wallet_minted(
+        env: &Env,
+        collection_id: u64,
+        phase: u32,
+        address: &Address,
+        minted: u32,
+    ) {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        write_persistent(
+            env,
+            &DataKey::PhaseWalletMinted(collection_id, schedule, phase, address.clone()),
+            &minted,
+        );
+    }
+
+    fn get_merkle_root(env: &Env, collection_id: u64, phase: u32) -> Option<BytesN<32>> {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        read_persistent(env, &DataKey::MerkleRoot(collection_id, schedule, phase))
+    }
+
+    fn set_merkle_root(env: &Env, collection_id: u64, phase: u32, root: &BytesN<32>) {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        write_persistent(
+            env,
+            &DataKey::MerkleRoot(collection_id, schedule, phase),
+            root,
+        );
+    }
+
+    fn get_allowlist_minted(env: &Env, collection_id: u64, phase: u32, address: &Address) -> u32 {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        read_persistent(
+            env,
+            &DataKey::AllowlistMinted(collection_id, schedule, phase, address.clone()),
+        )
+        .unwrap_or(0)
+    }
+
+    fn set_allowlist_minted(
+        env: &Env,
+        collection_id: u64,
+        phase: u32,
+        address: &Address,
+        minted: u32,
+    ) {
+        let schedule = Self::get_phase_schedule(env, collection_id);
+        write_persistent(
+            env,
+            &DataKey::AllowlistMinted(collection_id, schedule, phase, address.clone()),
+            &minted,
+        );
+    }
+
+    fn is_collection_paused(env: &Env, collection_id: u64) -> bool {
+        read_persistent(env, &DataKey::IsPaused(collection_id)).unwrap_or(false)
     }
 
     fn set_collection_paused(env: &Env, collection_id: u64, paused: bool) {
+        write_persistent(env, &DataKey::IsPaused(collection_id), &paused);
+    }
+
+    fn get_owner_token(env: &Env, collection_id: u64, owner: &Address, index: u32) -> Option<u32> {
+        read_persistent(
+            env,
+            &DataKey::OwnerToken(collection_id, owner.clone(), index),
+        )
+    }
+
+    fn set_owner_token(env: &Env, collection_id: u64, owner: &Address, index: u32, token_id: u32) {
+        write_persistent(
+            env,
+            &DataKey::OwnerToken(collection_id, owner.clone(), index),
+            &token_id,
+        );
+    }
+
+    fn remove_owner_token(env: &Env, collection_id: u64, owner: &Address, index: u32) {
+        env.storage().persistent().remove(&DataKey::OwnerToken(
+            collection_id,
+            owner.clone(),
+            index,
+        ));
+    }
+
+    fn get_owner_token_index(env: &Env, collection_id: u64, token_id: u32) -> Option<u32> {
+        read_persistent(env, &DataKey::OwnerTokenIndex(collection_id, token_id))
+    }
+
+    fn set_owner_token_index(env: &Env, collection_id: u64, token_id: u32, index: u32) {
+        write_persistent(
+            env,
+            &DataKey::OwnerTokenIndex(collection_id, token_id),
+            &index,
+        );
+    }
+
+    fn remove_owner_token_index(env: &Env, collection_id: u64, token_id: u32) {
         env.storage()
-            .instance()
-            .set(&DataKey::IsPaused(collection_id), &paused);
+            .persistent()
+            .remove(&DataKey::OwnerTokenIndex(collection_id, token_id));
     }
+
+    fn get_token_by_index(env: &Env, collection_id: u64, index: u32) -> Option<u32> {
+        read_persistent(env, &DataKey::TokenByIndex(collection_id, index))
+    }
+
+    fn set_token_by_index(env: &Env, collection_id: u64, index: u32, token_id: u32) {
+        write_persistent(env, &DataKey::TokenByIndex(collection_id, index), &token_id);
+    }
+
+    fn remove_token_by_index(env: &Env, collection_id: u64, index: u32) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TokenByIndex(collection_id, index));
+    }
+
+    fn get_token_index(env: &Env, collection_id: u64, token_id: u32) -> Option<u32> {
+        read_persistent(env, &DataKey::TokenIndex(collection_id, token_id))
+    }
+
+    fn set_token_index(env: &Env, collection_id: u64, token_id: u32, index: u32) {
+        write_persistent(env, &DataKey::TokenIndex(collection_id, token_id), &index);
+    }
+
+    fn remove_token_index(env: &Env, collection_id: u64, token_id: u32) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TokenIndex(collection_id, token_id));
+    }
+}
+
+// Typed reads of versioned structs fail cleanly until migrate() has walked
+// older entries forward, rather than misreading them. Token lookups use it too,
+// since v1 tokens stay in instance storage until migrated.
+pub(crate) fn require_current_schema(env: &Env) -> Result<(), Error> {
+    if <DataKey as Storage>::get_schema_version(env) < SCHEMA_VERSION {
+        return Err(Error::MigrationRequired);
+    }
+    Ok(())
+}
+
+// Instance storage shares one TTL, extended whenever factory state is touched.
+pub(crate) fn read_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let storage = env.storage().instance();
+    storage.extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+    storage.get(key)
+}
+
+fn write_instance<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    let storage = env.storage().instance();
+    storage.set(key, value);
+    storage.extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+// Persistent entries each carry their own TTL, extended on every read and write
+// so that live collections and tokens are never archived.
+pub(crate) fn read_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let storage = env.storage().persistent();
+    let value = storage.get(key);
+    if value.is_some() {
+        storage.extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
+    }
+    value
+}
+
+fn write_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    let storage = env.storage().persistent();
+    storage.set(key, value);
+    storage.extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, PERSISTENT_BUMP_AMOUNT);
 }
@@ -1,21 +1,110 @@
-use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
+extern crate std;
 
-use crate::{CollectionFactoryContract, CollectionFactoryContractClient};
+use std::boxed::Box;
+
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::{
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, AuthorizedFunction, AuthorizedInvocation, Events as _, Ledger as _, MockAuth,
+        MockAuthInvoke, SnapshotSourceInput,
+    },
+    token, vec,
+    xdr::{
+        ContractDataDurability, ContractDataEntry, ExtensionPoint, LedgerEntry, LedgerEntryData,
+        LedgerEntryExt, LedgerKey, LedgerKeyContractData, ScAddress, ScVal,
+    },
+    Address, BytesN, Env, Event as _, IntoVal, Map, String, Symbol, TryFromVal, Val,
+};
+
+use crate::{
+    errors::Error,
+    events::{self, EVENT_VERSION},
+    merkle,
+    migration::{
+        CollectionConfigV1, CollectionInfoV1, DataKeyV1, FactoryConfigV1,
+        FactoryConfigV1WithFeeToken, LegacyKey,
+    },
+    signature,
+    storage::{
+        AttributeDefinition, AttributeType, CollectionConfigUpdate, CollectionStatus, DataKey,
+        MetadataSchema, MintPhase, MintPrice, MintVoucher, Role, RoyaltyInfo, RoyaltyShare,
+        Storage, TokenMetadata, TransferPolicy, INSTANCE_BUMP_AMOUNT, PERSISTENT_BUMP_AMOUNT,
+    },
+    CollectionFactoryContract, CollectionFactoryContractClient,
+};
+
+// Built from contracts/collection_contract; refresh with `make test-fixtures`.
+mod collection_contract {
+    soroban_sdk::contractimport!(file = "testdata/collection_contract.wasm");
+}
+
+fn upload_collection_wasm(env: &Env) -> BytesN<32> {
+    env.deployer()
+        .upload_contract_wasm(collection_contract::WASM)
+}
+
+// Registers an initialized factory that can deploy collections
+fn setup_factory<'a>(env: &'a Env, owner: &Address) -> CollectionFactoryContractClient<'a> {
+    let contract_id = env.register(CollectionFactoryContract, ());
+    let client = CollectionFactoryContractClient::new(env, &contract_id);
+    client.initialize(owner);
+    client.set_collection_wasm_hash(owner, &upload_collection_wasm(env));
+    client
+}
+
+// A factory with one Basic collection created by `creator`
+fn setup_collection<'a>(
+    env: &'a Env,
+    owner: &Address,
+    creator: &Address,
+) -> (CollectionFactoryContractClient<'a>, u64) {
+    let client = setup_factory(env, owner);
+    let collection_id = create_test_collection(env, &client, creator);
+    (client, collection_id)
+}
+
+fn create_test_collection(
+    env: &Env,
+    client: &CollectionFactoryContractClient,
+    creator: &Address,
+) -> u64 {
+    create_collection_with_schema(env, client, creator, 0)
+}
+
+fn create_collection_with_schema(
+    env: &Env,
+    client: &CollectionFactoryContractClient,
+    creator: &Address,
+    metadata_schema: u32,
+) -> u64 {
+    client.create_collection(
+        creator,
+        &String::from_str(env, "Test Collection"),
+        &String::from_str(env, "TEST"),
+        &String::from_str(env, "A test NFT collection"),
+        &String::from_str(env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &true,
+        &500,
+        creator,
+        &metadata_schema,
+        &true,
+        &false,
+        &0,
+    )
+}
 
 #[test]
 fn test_initialize_and_create_collection() {
     let env = Env::default();
+    env.mock_all_auths();
 
     // Create test addresses
     let owner = Address::generate(&env);
     let creator = Address::generate(&env);
 
-    // Deploy contract
-    let contract_id = env.register(CollectionFactoryContract, ());
-    let client = CollectionFactoryContractClient::new(&env, &contract_id);
-
-    // Initialize factory
-    client.initialize(&owner);
+    let client = setup_factory(&env, &owner);
 
     // Create collection
     let collection_id = client.create_collection(
@@ -31,6 +120,7 @@ fn test_initialize_and_create_collection() {
         &0, // Basic metadata schema
         &true,
         &false,
+        &0,
     );
 
     assert!(collection_id > 0);
@@ -40,13 +130,14 @@ fn test_initialize_and_create_collection() {
     assert_eq!(count, 1);
 
     // Verify collection address
-    let _address = client.get_collection_address(&collection_id);
-    // Note: is_contract() method doesn't exist in current Soroban SDK
+    let address = client.get_collection_address(&collection_id);
+    assert_ne!(address, client.address);
 }
 
 #[test]
 fn test_mint_and_transfer() {
     let env = Env::default();
+    env.mock_all_auths();
 
     // Create test addresses
     let owner = Address::generate(&env);
@@ -54,9 +145,7 @@ fn test_mint_and_transfer() {
     let recipient = Address::generate(&env);
 
     // Deploy and initialize
-    let contract_id = env.register(CollectionFactoryContract, ());
-    let client = CollectionFactoryContractClient::new(&env, &contract_id);
-    client.initialize(&owner);
+    let client = setup_factory(&env, &owner);
 
     // Create collection
     let collection_id = client.create_collection(
@@ -72,6 +161,7 @@ fn test_mint_and_transfer() {
         &0,
         &true,
         &false,
+        &0,
     );
 
     // Mint token
@@ -109,13 +199,12 @@ fn test_mint_and_transfer() {
 #[test]
 fn test_batch_mint() {
     let env = Env::default();
+    env.mock_all_auths();
 
     let owner = Address::generate(&env);
     let creator = Address::generate(&env);
 
-    let contract_id = env.register(CollectionFactoryContract, ());
-    let client = CollectionFactoryContractClient::new(&env, &contract_id);
-    client.initialize(&owner);
+    let client = setup_factory(&env, &owner);
 
     let collection_id = client.create_collection(
         &creator,
@@ -130,6 +219,7 @@ fn test_batch_mint() {
         &0,
         &true,
         &false,
+        &0,
     );
 
     // Create URIs for batch mint
@@ -156,14 +246,13 @@ fn test_batch_mint() {
 #[test]
 fn test_royalty_management() {
     let env = Env::default();
+    env.mock_all_auths();
 
     let owner = Address::generate(&env);
     let creator = Address::generate(&env);
     let royalty_recipient = Address::generate(&env);
 
-    let contract_id = env.register(CollectionFactoryContract, ());
-    let client = CollectionFactoryContractClient::new(&env, &contract_id);
-    client.initialize(&owner);
+    let client = setup_factory(&env, &owner);
 
     let collection_id = client.create_collection(
         &creator,
@@ -178,6 +267,7 @@ fn test_royalty_management() {
         &0,
         &true,
         &false,
+        &0,
     );
 
     // Get royalty info
@@ -198,3 +288,4864 @@ fn test_royalty_management() {
     assert_eq!(updated_info.recipient, new_recipient);
     assert_eq!(updated_info.percentage, 750);
 }
+
+#[test]
+fn test_create_collection_deploys_collection_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let contract_id = env.register(CollectionFactoryContract, ());
+    let client = CollectionFactoryContractClient::new(&env, &contract_id);
+    client.initialize(&owner);
+
+    // Collections cannot be created before the collection Wasm is set
+    let result = client.try_create_collection(
+        &creator,
+        &String::from_str(&env, "First Collection"),
+        &String::from_str(&env, "FIRST"),
+        &String::from_str(&env, "The first collection"),
+        &String::from_str(&env, "https://api.nftopia.com/first/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::CollectionWasmNotSet)));
+
+    // Only the factory owner can set the collection Wasm
+    let wasm_hash = upload_collection_wasm(&env);
+    let result = client.try_set_collection_wasm_hash(&creator, &wasm_hash);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    client.set_collection_wasm_hash(&owner, &wasm_hash);
+    assert_eq!(client.get_collection_wasm_hash(), Some(wasm_hash));
+
+    let first_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "First Collection"),
+        &String::from_str(&env, "FIRST"),
+        &String::from_str(&env, "The first collection"),
+        &String::from_str(&env, "https://api.nftopia.com/first/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    let second_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Second Collection"),
+        &String::from_str(&env, "SECOND"),
+        &String::from_str(&env, "The second collection"),
+        &String::from_str(&env, "https://api.nftopia.com/second/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+
+    // Each collection has its own deployed contract
+    let first_address = client.get_collection_address(&first_id);
+    let second_address = client.get_collection_address(&second_id);
+    assert_ne!(first_address, second_address);
+    assert_ne!(first_address, contract_id);
+    assert_ne!(second_address, contract_id);
+
+    let first = collection_contract::Client::new(&env, &first_address);
+    let second = collection_contract::Client::new(&env, &second_address);
+    assert_eq!(first.factory(), contract_id);
+    assert_eq!(first.collection_id(), first_id);
+    assert_eq!(first.name(), String::from_str(&env, "First Collection"));
+    assert_eq!(second.collection_id(), second_id);
+    assert_eq!(second.symbol(), String::from_str(&env, "SECOND"));
+
+    // The collection contract reads and moves tokens held by the factory
+    let token_id = client.mint(
+        &first_id,
+        &creator,
+        &String::from_str(&env, "https://api.nftopia.com/first/1"),
+        &None,
+    );
+    assert_eq!(first.owner_of(&token_id), creator);
+    assert_eq!(first.balance(&creator), 1);
+    assert_eq!(second.total_supply(), 0);
+
+    first.transfer(&creator, &recipient, &token_id);
+    assert_eq!(client.owner_of(&first_id, &token_id), recipient);
+    assert_eq!(first.total_supply(), 1);
+}
+
+// SEP-0050 conformance: the deployed collection contract exposes the standard
+// method names, argument orders and event shapes. The expected events are
+// written out from the spec rather than imported from the contract, whose
+// generated client does not keep each event's data format.
+#[test]
+fn test_sep0050_metadata_and_queries() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let holder = Address::generate(&env);
+
+    assert_eq!(collection.name(), String::from_str(&env, "Test Collection"));
+    assert_eq!(collection.symbol(), String::from_str(&env, "TEST"));
+    assert_eq!(collection.balance(&holder), 0);
+
+    let token_id = collection.mint(
+        &holder,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), holder.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.balance(&holder), 1);
+    assert_eq!(collection.owner_of(&token_id), holder);
+    assert_eq!(
+        collection.token_uri(&token_id),
+        String::from_str(&env, "https://api.nftopia.com/metadata/1")
+    );
+    assert_eq!(collection.get_approved(&token_id), None);
+    assert!(!collection.is_approved_for_all(&holder, &creator));
+
+    // Unknown tokens are rejected, not reported as unowned
+    assert!(collection.try_owner_of(&99).is_err());
+    assert!(collection.try_token_uri(&99).is_err());
+}
+
+#[test]
+fn test_sep0050_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    let token_id = collection.mint(
+        &from,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    collection.transfer(&from, &to, &token_id);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), from.clone(), to.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            from.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    collection.address.clone(),
+                    Symbol::new(&env, "transfer"),
+                    (&from, &to, token_id).into_val(&env),
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        client.address.clone(),
+                        Symbol::new(&env, "transfer"),
+                        (collection_id, &from, &to, token_id).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }],
+            }
+        )]
+    );
+    assert_eq!(collection.owner_of(&token_id), to);
+    assert_eq!(collection.balance(&from), 0);
+    assert_eq!(collection.balance(&to), 1);
+
+    // Only the current owner can transfer
+    assert!(collection.try_transfer(&from, &to, &token_id).is_err());
+}
+
+#[test]
+fn test_sep0050_approve_and_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let (client, collection_id) = setup_collection(&env, &admin, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_id = collection.mint(
+        &owner,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    // A spender without approval is rejected
+    assert!(collection
+        .try_transfer_from(&spender, &owner, &buyer, &token_id)
+        .is_err());
+
+    // Approvals cannot end in the past
+    assert_eq!(
+        collection.try_approve(&owner, &spender, &token_id, &99),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::InvalidLiveUntilLedger as u32
+        )))
+    );
+
+    collection.approve(&owner, &spender, &token_id, &200);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve"), owner.clone(), token_id).into_val(&env),
+                (spender.clone(), 200_u32).into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.get_approved(&token_id), Some(spender.clone()));
+
+    collection.transfer_from(&spender, &owner, &buyer, &token_id);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), owner.clone(), buyer.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            spender.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    collection.address.clone(),
+                    Symbol::new(&env, "transfer_from"),
+                    (&spender, &owner, &buyer, token_id).into_val(&env),
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        client.address.clone(),
+                        Symbol::new(&env, "transfer_from"),
+                        (collection_id, &spender, &owner, &buyer, token_id).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }],
+            }
+        )]
+    );
+    assert_eq!(collection.owner_of(&token_id), buyer);
+
+    // The approval is cleared by the transfer
+    assert_eq!(collection.get_approved(&token_id), None);
+}
+
+#[test]
+fn test_sep0050_approve_for_all() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let (client, collection_id) = setup_collection(&env, &admin, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_id = collection.mint(
+        &owner,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    assert_eq!(
+        collection.try_approve_for_all(&owner, &operator, &99),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::InvalidLiveUntilLedger as u32
+        )))
+    );
+
+    collection.approve_for_all(&owner, &operator, &200);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve_for_all"), owner.clone()).into_val(&env),
+                (operator.clone(), 200_u32).into_val(&env),
+            )
+        ]
+    );
+    assert!(collection.is_approved_for_all(&owner, &operator));
+
+    // The operator moves any of the owner's tokens
+    let second_id = collection.mint(
+        &owner,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/2"),
+        &None,
+    );
+    collection.transfer_from(&operator, &owner, &buyer, &second_id);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "transfer"), owner.clone(), buyer.clone()).into_val(&env),
+                second_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.owner_of(&second_id), buyer);
+
+    // A live_until_ledger of 0 revokes the operator
+    collection.approve_for_all(&owner, &operator, &0);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve_for_all"), owner.clone()).into_val(&env),
+                (operator.clone(), 0_u32).into_val(&env),
+            )
+        ]
+    );
+    assert!(!collection.is_approved_for_all(&owner, &operator));
+    assert!(collection
+        .try_transfer_from(&operator, &owner, &buyer, &token_id)
+        .is_err());
+}
+
+#[test]
+fn test_sep0050_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let (client, collection_id) = setup_collection(&env, &admin, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let owner = Address::generate(&env);
+    let token_id = collection.mint(
+        &owner,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    collection.burn(&owner, &token_id);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "burn"), owner.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.balance(&owner), 0);
+    assert!(collection.try_owner_of(&token_id).is_err());
+}
+
+#[test]
+fn test_sep0050_token_calls_go_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let holder = Address::generate(&env);
+    let to = Address::generate(&env);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/1");
+    let token_id = collection.mint(&holder, &uri, &None);
+
+    // Called directly, the factory would change state without the collection
+    // publishing an event, so it insists on the collection's authorization
+    let mint = MockAuthInvoke {
+        contract: &client.address,
+        fn_name: "mint",
+        args: (collection_id, &holder, &uri, None::<Map<String, String>>).into_val(&env),
+        sub_invokes: &[],
+    };
+    assert!(client
+        .mock_auths(&[MockAuth {
+            address: &holder,
+            invoke: &mint,
+        }])
+        .try_mint(&collection_id, &holder, &uri, &None)
+        .is_err());
+
+    let transfer = MockAuthInvoke {
+        contract: &client.address,
+        fn_name: "transfer",
+        args: (collection_id, &holder, &to, token_id).into_val(&env),
+        sub_invokes: &[],
+    };
+    assert!(client
+        .mock_auths(&[MockAuth {
+            address: &holder,
+            invoke: &transfer,
+        }])
+        .try_transfer(&collection_id, &holder, &to, &token_id)
+        .is_err());
+
+    let burn = MockAuthInvoke {
+        contract: &client.address,
+        fn_name: "burn",
+        args: (collection_id, &holder, token_id).into_val(&env),
+        sub_invokes: &[],
+    };
+    assert!(client
+        .mock_auths(&[MockAuth {
+            address: &holder,
+            invoke: &burn,
+        }])
+        .try_burn(&collection_id, &holder, &token_id)
+        .is_err());
+    assert_eq!(collection.owner_of(&token_id), holder);
+    assert_eq!(collection.total_supply(), 1);
+}
+
+#[test]
+fn test_transfer_requires_owner_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let victim = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &victim,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    // The attacker signs, but the token belongs to the victim
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "transfer",
+                args: (collection_id, &victim, &attacker, token_id).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_transfer(&collection_id, &victim, &attacker, &token_id);
+    assert!(result.is_err());
+
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "burn",
+                args: (collection_id, &victim, token_id).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_burn(&collection_id, &victim, &token_id);
+    assert!(result.is_err());
+    assert_eq!(client.owner_of(&collection_id, &token_id), victim);
+
+    // The owner's own signature moves the token through the collection
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    collection
+        .mock_auths(&[MockAuth {
+            address: &victim,
+            invoke: &MockAuthInvoke {
+                contract: &collection.address,
+                fn_name: "transfer",
+                args: (&victim, &attacker, token_id).into_val(&env),
+                sub_invokes: &[MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "transfer",
+                    args: (collection_id, &victim, &attacker, token_id).into_val(&env),
+                    sub_invokes: &[],
+                }],
+            },
+        }])
+        .transfer(&victim, &attacker, &token_id);
+    assert_eq!(client.owner_of(&collection_id, &token_id), attacker);
+}
+
+#[test]
+fn test_set_approval_for_all_requires_owner_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let victim = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &victim,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    // The attacker cannot make themselves an operator for the victim
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "set_approval_for_all",
+                args: (collection_id, &victim, &attacker, 100_u32).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_set_approval_for_all(&collection_id, &victim, &attacker, &100);
+    assert!(result.is_err());
+    assert!(!client.is_approved_for_all(&collection_id, &victim, &attacker));
+
+    // Without the approval, transfer_from by the attacker is rejected
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let result = collection
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &collection.address,
+                fn_name: "transfer_from",
+                args: (&attacker, &victim, &attacker, token_id).into_val(&env),
+                sub_invokes: &[MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "transfer_from",
+                    args: (collection_id, &attacker, &victim, &attacker, token_id).into_val(&env),
+                    sub_invokes: &[],
+                }],
+            },
+        }])
+        .try_transfer_from(&attacker, &victim, &attacker, &token_id);
+    assert_eq!(
+        result,
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::NotApproved as u32
+        )))
+    );
+
+    // The victim's signature is recorded as the one authorizing the approval
+    env.mock_all_auths();
+    collection.approve_for_all(&victim, &attacker, &100);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            victim.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    collection.address.clone(),
+                    Symbol::new(&env, "approve_for_all"),
+                    (&victim, &attacker, 100_u32).into_val(&env),
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        client.address.clone(),
+                        Symbol::new(&env, "set_approval_for_all"),
+                        (collection_id, &victim, &attacker, 100_u32).into_val(&env),
+                    )),
+                    sub_invocations: std::vec![],
+                }],
+            }
+        )]
+    );
+    assert!(client.is_approved_for_all(&collection_id, &victim, &attacker));
+}
+
+#[test]
+fn test_approvals_expire() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let first_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/1");
+    let token_id = client.mint(&first_id, &holder, &uri, &None);
+
+    // Approvals must end between the current ledger and the maximum TTL
+    assert_eq!(
+        client.try_approve(&first_id, &holder, &spender, &token_id, &99),
+        Err(Ok(Error::InvalidLiveUntilLedger))
+    );
+    let too_late = env.ledger().max_live_until_ledger() + 1;
+    assert_eq!(
+        client.try_set_approval_for_all(&first_id, &holder, &operator, &too_late),
+        Err(Ok(Error::InvalidLiveUntilLedger))
+    );
+
+    // Approvals hold through live_until_ledger and read as absent after it
+    client.approve(&first_id, &holder, &spender, &token_id, &150);
+    client.set_approval_for_all(&first_id, &holder, &operator, &150);
+    env.ledger().set_sequence_number(150);
+    assert_eq!(
+        client.get_approved(&first_id, &token_id),
+        Some(spender.clone())
+    );
+    assert!(client.is_approved_for_all(&first_id, &holder, &operator));
+
+    env.ledger().set_sequence_number(151);
+    assert_eq!(client.get_approved(&first_id, &token_id), None);
+    assert!(!client.is_approved_for_all(&first_id, &holder, &operator));
+    assert_eq!(
+        client.try_transfer_from(&first_id, &spender, &holder, &spender, &token_id),
+        Err(Ok(Error::NotApproved))
+    );
+    assert_eq!(
+        client.try_transfer_from(&first_id, &operator, &holder, &operator, &token_id),
+        Err(Ok(Error::NotApproved))
+    );
+
+    // A live_until_ledger of 0 clears a token approval
+    client.approve(&first_id, &holder, &spender, &token_id, &300);
+    client.approve(&first_id, &holder, &spender, &token_id, &0);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::Approve {
+            collection_id: first_id,
+            version: EVENT_VERSION,
+            owner: holder.clone(),
+            approved: spender.clone(),
+            token_id,
+            live_until_ledger: 0,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.get_approved(&first_id, &token_id), None);
+}
+
+#[test]
+fn test_approvals_expire_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let token_id = collection.mint(
+        &holder,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    let too_late = env.ledger().max_live_until_ledger() + 1;
+    assert_eq!(
+        collection.try_approve_for_all(&holder, &operator, &too_late),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::InvalidLiveUntilLedger as u32
+        )))
+    );
+
+    // Approvals made on the collection expire on the same ledger as the
+    // factory's
+    collection.approve(&holder, &spender, &token_id, &150);
+    collection.approve_for_all(&holder, &operator, &150);
+    env.ledger().set_sequence_number(150);
+    assert_eq!(collection.get_approved(&token_id), Some(spender.clone()));
+    assert!(collection.is_approved_for_all(&holder, &operator));
+
+    env.ledger().set_sequence_number(151);
+    assert_eq!(collection.get_approved(&token_id), None);
+    assert!(!collection.is_approved_for_all(&holder, &operator));
+    assert_eq!(
+        collection.try_transfer_from(&spender, &holder, &spender, &token_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::NotApproved as u32
+        )))
+    );
+    assert_eq!(
+        collection.try_transfer_from(&operator, &holder, &operator, &token_id),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::NotApproved as u32
+        )))
+    );
+
+    // A renewed approval works again
+    collection.approve(&holder, &spender, &token_id, &300);
+    collection.transfer_from(&spender, &holder, &spender, &token_id);
+    assert_eq!(collection.owner_of(&token_id), spender);
+}
+
+#[test]
+fn test_revoke_all_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let first_id = create_test_collection(&env, &client, &creator);
+    let second_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/1");
+    let token_id = client.mint(&first_id, &holder, &uri, &None);
+    let other_token_id = client.mint(&second_id, &holder, &uri, &None);
+
+    // Revoking all approvals covers every collection at once
+    client.approve(&first_id, &holder, &spender, &token_id, &300);
+    client.set_approval_for_all(&first_id, &holder, &operator, &300);
+    client.set_approval_for_all(&second_id, &holder, &operator, &300);
+    client.revoke_all_approvals(&holder);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::ApprovalsRevoked {
+            version: EVENT_VERSION,
+            owner: holder.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.get_approved(&first_id, &token_id), None);
+    assert!(!client.is_approved_for_all(&first_id, &holder, &operator));
+    assert!(!client.is_approved_for_all(&second_id, &holder, &operator));
+    assert_eq!(
+        client.try_transfer_from(&second_id, &operator, &holder, &operator, &other_token_id),
+        Err(Ok(Error::NotApproved))
+    );
+
+    // Other owners' approvals are untouched, and new grants work again
+    client.set_approval_for_all(&first_id, &spender, &operator, &300);
+    client.revoke_all_approvals(&holder);
+    assert!(client.is_approved_for_all(&first_id, &spender, &operator));
+
+    client.set_approval_for_all(&second_id, &holder, &operator, &300);
+    client.transfer_from(&second_id, &operator, &holder, &operator, &other_token_id);
+    assert_eq!(client.owner_of(&second_id, &other_token_id), operator);
+}
+
+fn sign_permit(
+    env: &Env,
+    contract_id: &Address,
+    signing_key: &SigningKey,
+    permit: &signature::Permit,
+) -> BytesN<64> {
+    let message = env.as_contract(contract_id, || signature::permit_message(env, permit));
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(env, &signing_key.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_permit_requires_registered_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &holder,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let permit = signature::Permit {
+        collection_id,
+        owner: holder.clone(),
+        spender: spender.clone(),
+        token_id,
+        deadline: 200,
+        nonce: 0,
+    };
+    let signed = sign_permit(&env, &client.address, &signing_key, &permit);
+
+    // Permits are only accepted from the key the owner registered
+    assert_eq!(
+        client.try_permit(
+            &collection_id,
+            &holder,
+            &spender,
+            &token_id,
+            &200,
+            &0,
+            &public_key,
+            &signed
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_permit_key(&holder, &Some(public_key.clone()));
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::PermitKeyUpdated {
+            version: EVENT_VERSION,
+            owner: holder.clone(),
+            public_key: Some(public_key.clone()),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.permit_key(&holder), Some(public_key.clone()));
+
+    // Removing the key stops further permits
+    client.set_permit_key(&holder, &None);
+    let next = signature::Permit { nonce: 1, ..permit };
+    assert_eq!(
+        client.try_permit(
+            &collection_id,
+            &holder,
+            &spender,
+            &token_id,
+            &200,
+            &1,
+            &public_key,
+            &sign_permit(&env, &client.address, &signing_key, &next)
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_permit_rejects_invalid_permits() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &holder,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let permit = signature::Permit {
+        collection_id,
+        owner: holder.clone(),
+        spender: spender.clone(),
+        token_id,
+        deadline: 200,
+        nonce: 0,
+    };
+    let signed = sign_permit(&env, &client.address, &signing_key, &permit);
+
+    client.set_permit_key(&holder, &Some(public_key.clone()));
+
+    // Expired deadlines and out-of-order nonces are rejected
+    let expired = signature::Permit {
+        deadline: 99,
+        ..permit.clone()
+    };
+    assert_eq!(
+        client.try_permit(
+            &collection_id,
+            &holder,
+            &spender,
+            &token_id,
+            &99,
+            &0,
+            &public_key,
+            &sign_permit(&env, &client.address, &signing_key, &expired)
+        ),
+        Err(Ok(Error::PermitExpired))
+    );
+    let skipped = signature::Permit {
+        nonce: 1,
+        ..permit.clone()
+    };
+    assert_eq!(
+        client.try_permit(
+            &collection_id,
+            &holder,
+            &spender,
+            &token_id,
+            &200,
+            &1,
+            &public_key,
+            &sign_permit(&env, &client.address, &signing_key, &skipped)
+        ),
+        Err(Ok(Error::InvalidNonce))
+    );
+
+    // A signature does not carry over to a different spender
+    let stranger = Address::generate(&env);
+    assert!(client
+        .try_permit(
+            &collection_id,
+            &holder,
+            &stranger,
+            &token_id,
+            &200,
+            &0,
+            &public_key,
+            &signed
+        )
+        .is_err());
+    assert_eq!(client.permit_nonce(&holder), 0);
+}
+
+#[test]
+fn test_permit() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &holder,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let permit = signature::Permit {
+        collection_id,
+        owner: holder.clone(),
+        spender: spender.clone(),
+        token_id,
+        deadline: 200,
+        nonce: 0,
+    };
+    let signed = sign_permit(&env, &client.address, &signing_key, &permit);
+
+    client.set_permit_key(&holder, &Some(public_key.clone()));
+
+    // Anyone can submit a valid permit; it writes an ordinary approval
+    client.permit(
+        &collection_id,
+        &holder,
+        &spender,
+        &token_id,
+        &200,
+        &0,
+        &public_key,
+        &signed,
+    );
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::Approve {
+            collection_id,
+            version: EVENT_VERSION,
+            owner: holder.clone(),
+            approved: spender.clone(),
+            token_id,
+            live_until_ledger: 200,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(
+        client.get_approved(&collection_id, &token_id),
+        Some(spender.clone())
+    );
+    assert_eq!(client.permit_nonce(&holder), 1);
+
+    // The nonce is consumed, so the permit cannot be replayed
+    assert_eq!(
+        client.try_permit(
+            &collection_id,
+            &holder,
+            &spender,
+            &token_id,
+            &200,
+            &0,
+            &public_key,
+            &signed
+        ),
+        Err(Ok(Error::InvalidNonce))
+    );
+
+    client.transfer_from(&collection_id, &spender, &holder, &spender, &token_id);
+    assert_eq!(client.owner_of(&collection_id, &token_id), spender);
+}
+
+#[test]
+fn test_permit_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_sequence_number(100);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let token_id = collection.mint(
+        &holder,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let permit = signature::Permit {
+        collection_id,
+        owner: holder.clone(),
+        spender: spender.clone(),
+        token_id,
+        deadline: 200,
+        nonce: 0,
+    };
+    let signed = sign_permit(&env, &client.address, &signing_key, &permit);
+    client.set_permit_key(&holder, &Some(public_key.clone()));
+
+    // The collection forwards the permit and publishes a SEP-0050 approve
+    collection.permit(&holder, &spender, &token_id, &200, &0, &public_key, &signed);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "approve"), holder.clone(), token_id).into_val(&env),
+                (spender.clone(), 200_u32).into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.get_approved(&token_id), Some(spender.clone()));
+
+    assert_eq!(
+        collection.try_permit(&holder, &spender, &token_id, &200, &0, &public_key, &signed),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::InvalidNonce as u32
+        )))
+    );
+
+    collection.transfer_from(&spender, &holder, &spender, &token_id);
+    assert_eq!(collection.owner_of(&token_id), spender);
+}
+
+fn sign_voucher(
+    env: &Env,
+    contract_id: &Address,
+    signing_key: &SigningKey,
+    collection_id: u64,
+    voucher: &MintVoucher,
+) -> BytesN<64> {
+    let message = env.as_contract(contract_id, || {
+        signature::voucher_message(env, collection_id, voucher)
+    });
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(env, &signing_key.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_minter_keys() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+    client.set_fee_token(&owner, &sac.address());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+
+    let signing_key = SigningKey::from_bytes(&[9; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let voucher = MintVoucher {
+        voucher_id: BytesN::from_array(&env, &[1; 32]),
+        signer: public_key.clone(),
+        uri: String::from_str(&env, "https://api.nftopia.com/metadata/lazy-1"),
+        attributes: Map::new(&env),
+        price: 300,
+        price_token: Some(sac.address()),
+        expires_at: 2_000,
+    };
+    let signed = sign_voucher(&env, &client.address, &signing_key, collection_id, &voucher);
+
+    // Only minters can authorize signing keys, and only authorized keys count
+    assert_eq!(
+        client.try_set_minter_key(&collection_id, &stranger, &public_key, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_redeem_voucher(&collection_id, &voucher, &signed, &buyer),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_minter_key(&collection_id, &creator, &public_key, &true);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::MinterKeyUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            public_key: public_key.clone(),
+            authorized: true,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert!(client.is_minter_key(&collection_id, &public_key));
+}
+
+#[test]
+fn test_redeem_voucher_rejects_invalid_vouchers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+    client.set_fee_token(&owner, &sac.address());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+
+    let signing_key = SigningKey::from_bytes(&[9; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let voucher = MintVoucher {
+        voucher_id: BytesN::from_array(&env, &[1; 32]),
+        signer: public_key.clone(),
+        uri: String::from_str(&env, "https://api.nftopia.com/metadata/lazy-1"),
+        attributes: Map::new(&env),
+        price: 300,
+        price_token: Some(sac.address()),
+        expires_at: 2_000,
+    };
+    let signed = sign_voucher(&env, &client.address, &signing_key, collection_id, &voucher);
+
+    client.set_minter_key(&collection_id, &creator, &public_key, &true);
+
+    // Vouchers priced in anything but the fee token are rejected before the
+    // signature is checked or a token is minted
+    let foreign_token = env.register_stellar_asset_contract_v2(owner.clone());
+    let foreign = MintVoucher {
+        price_token: Some(foreign_token.address()),
+        ..voucher.clone()
+    };
+    assert_eq!(
+        client.try_redeem_voucher(&collection_id, &foreign, &signed, &buyer),
+        Err(Ok(Error::InvalidConfig))
+    );
+    let unpriced = MintVoucher {
+        price_token: None,
+        ..voucher.clone()
+    };
+    assert_eq!(
+        client.try_redeem_voucher(&collection_id, &unpriced, &signed, &buyer),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert!(!client.is_voucher_redeemed(&collection_id, &voucher.voucher_id));
+    assert_eq!(client.total_supply(&collection_id), 0);
+
+    // A tampered voucher no longer matches its signature
+    let discounted = MintVoucher {
+        price: 1,
+        ..voucher.clone()
+    };
+    assert!(client
+        .try_redeem_voucher(&collection_id, &discounted, &signed, &buyer)
+        .is_err());
+
+    // Vouchers signed for one collection do not mint in another
+    let other_id = create_test_collection(&env, &client, &creator);
+    client.set_minter_key(&other_id, &creator, &public_key, &true);
+    assert!(client
+        .try_redeem_voucher(&other_id, &voucher, &signed, &buyer)
+        .is_err());
+}
+
+#[test]
+fn test_redeem_voucher() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let payment_token = token::Client::new(&env, &sac.address());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+    client.set_fee_token(&owner, &sac.address());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+
+    let signing_key = SigningKey::from_bytes(&[9; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let voucher = MintVoucher {
+        voucher_id: BytesN::from_array(&env, &[1; 32]),
+        signer: public_key.clone(),
+        uri: String::from_str(&env, "https://api.nftopia.com/metadata/lazy-1"),
+        attributes: Map::new(&env),
+        price: 300,
+        price_token: Some(sac.address()),
+        expires_at: 2_000,
+    };
+    let signed = sign_voucher(&env, &client.address, &signing_key, collection_id, &voucher);
+
+    client.set_minter_key(&collection_id, &creator, &public_key, &true);
+
+    // Redemption mints to the buyer and charges the voucher price
+    let token_id = client.redeem_voucher(&collection_id, &voucher, &signed, &buyer);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [
+            events::Mint {
+                collection_id,
+                version: EVENT_VERSION,
+                to: buyer.clone(),
+                token_id,
+            }
+            .to_xdr(&env, &client.address),
+            events::MintPaid {
+                collection_id,
+                version: EVENT_VERSION,
+                payer: buyer.clone(),
+                token: sac.address(),
+                amount: 300,
+                platform_share: 0,
+            }
+            .to_xdr(&env, &client.address),
+            events::VoucherRedeemed {
+                collection_id,
+                version: EVENT_VERSION,
+                voucher_id: voucher.voucher_id.clone(),
+                buyer: buyer.clone(),
+                token_id,
+            }
+            .to_xdr(&env, &client.address),
+        ]
+    );
+    assert_eq!(client.owner_of(&collection_id, &token_id), buyer);
+    assert_eq!(client.token_uri(&collection_id, &token_id), voucher.uri);
+    assert_eq!(payment_token.balance(&buyer), 700);
+    assert_eq!(payment_token.balance(&creator), 300);
+    assert!(client.is_voucher_redeemed(&collection_id, &voucher.voucher_id));
+
+    // Each voucher mints once
+    assert_eq!(
+        client.try_redeem_voucher(&collection_id, &voucher, &signed, &stranger),
+        Err(Ok(Error::VoucherAlreadyRedeemed))
+    );
+
+    // Expired vouchers and revoked keys are rejected
+    let free = MintVoucher {
+        voucher_id: BytesN::from_array(&env, &[2; 32]),
+        price: 0,
+        price_token: None,
+        ..voucher.clone()
+    };
+    let signed_free = sign_voucher(&env, &client.address, &signing_key, collection_id, &free);
+    env.ledger().set_timestamp(2_001);
+    assert_eq!(
+        client.try_redeem_voucher(&collection_id, &free, &signed_free, &buyer),
+        Err(Ok(Error::VoucherExpired))
+    );
+    env.ledger().set_timestamp(2_000);
+    client.set_minter_key(&collection_id, &creator, &public_key, &false);
+    assert_eq!(
+        client.try_redeem_voucher(&collection_id, &free, &signed_free, &buyer),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_minter_key(&collection_id, &creator, &public_key, &true);
+    client.redeem_voucher(&collection_id, &free, &signed_free, &buyer);
+    assert_eq!(client.balance_of(&collection_id, &buyer), 2);
+    assert_eq!(payment_token.balance(&buyer), 700);
+}
+
+#[test]
+fn test_redeem_voucher_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let payment_token = token::Client::new(&env, &sac.address());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+    client.set_fee_token(&owner, &sac.address());
+
+    let signing_key = SigningKey::from_bytes(&[9; 32]);
+    let public_key = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+    let voucher = MintVoucher {
+        voucher_id: BytesN::from_array(&env, &[1; 32]),
+        signer: public_key.clone(),
+        uri: String::from_str(&env, "https://api.nftopia.com/metadata/lazy-1"),
+        attributes: Map::new(&env),
+        price: 300,
+        price_token: Some(sac.address()),
+        expires_at: 2_000,
+    };
+    let signed = sign_voucher(&env, &client.address, &signing_key, collection_id, &voucher);
+    client.set_minter_key(&collection_id, &creator, &public_key, &true);
+
+    // The collection's contract type carries the same fields
+    let voucher = collection_contract::MintVoucher {
+        voucher_id: voucher.voucher_id,
+        signer: voucher.signer,
+        uri: voucher.uri,
+        attributes: voucher.attributes,
+        price: voucher.price,
+        price_token: voucher.price_token,
+        expires_at: voucher.expires_at,
+    };
+
+    // The collection publishes a SEP-0050 mint to the buyer, who pays
+    let token_id = collection.redeem_voucher(&voucher, &signed, &buyer);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), buyer.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.owner_of(&token_id), buyer);
+    assert_eq!(collection.token_uri(&token_id), voucher.uri);
+    assert_eq!(payment_token.balance(&buyer), 700);
+    assert_eq!(payment_token.balance(&creator), 300);
+
+    assert_eq!(
+        collection.try_redeem_voucher(&voucher, &signed, &buyer),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::VoucherAlreadyRedeemed as u32
+        )))
+    );
+}
+
+#[test]
+fn test_admin_functions_require_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+
+    // Passing the owner's address without their signature is rejected
+    let result = client.mock_auths(&[]).try_set_factory_fee(&owner, &100);
+    assert!(result.is_err());
+    let result = client
+        .mock_auths(&[])
+        .try_set_paused(&collection_id, &creator, &true);
+    assert!(result.is_err());
+    let result =
+        client
+            .mock_auths(&[])
+            .try_set_whitelist(&collection_id, &creator, &creator, &true);
+    assert!(result.is_err());
+
+    env.mock_all_auths();
+    client.set_factory_fee(&owner, &100);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            owner.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    client.address.clone(),
+                    Symbol::new(&env, "set_factory_fee"),
+                    (&owner, 100_i128).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+
+    client.set_paused(&collection_id, &creator, &true);
+    assert_eq!(
+        env.auths(),
+        std::vec![(
+            creator.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    client.address.clone(),
+                    Symbol::new(&env, "set_paused"),
+                    (collection_id, &creator, true).into_val(&env),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_creation_fee_paid_in_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let fee_token = token::Client::new(&env, &sac.address());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&creator, &150);
+
+    // A fee without a token to charge it in is rejected
+    client.set_factory_fee(&owner, &100);
+    let result = client.try_create_collection(
+        &creator,
+        &String::from_str(&env, "Test Collection"),
+        &String::from_str(&env, "TEST"),
+        &String::from_str(&env, "A test NFT collection"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::FeeTokenNotSet)));
+
+    client.set_fee_token(&owner, &sac.address());
+    create_test_collection(&env, &client, &creator);
+
+    assert_eq!(fee_token.balance(&creator), 50);
+    assert_eq!(fee_token.balance(&client.address), 100);
+    assert_eq!(client.get_factory_config().accumulated_fees, 100);
+
+    // The creator cannot cover a second fee
+    let result = client.try_create_collection(
+        &creator,
+        &String::from_str(&env, "Second Collection"),
+        &String::from_str(&env, "SECOND"),
+        &String::from_str(&env, "Another test NFT collection"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    assert_eq!(result, Err(Ok(Error::InsufficientFee)));
+    assert_eq!(client.get_collection_count(), 1);
+
+    // The token cannot change while fees are held in it
+    let other_sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let result = client.try_set_fee_token(&owner, &other_sac.address());
+    assert_eq!(result, Err(Ok(Error::InvalidConfig)));
+
+    // Withdrawals pay out to the recipient
+    let result = client.try_withdraw_fees(&owner, &treasury, &101);
+    assert_eq!(result, Err(Ok(Error::InsufficientFee)));
+
+    client.withdraw_fees(&owner, &treasury, &60);
+    assert_eq!(fee_token.balance(&treasury), 60);
+    assert_eq!(fee_token.balance(&client.address), 40);
+    assert_eq!(client.get_factory_config().accumulated_fees, 40);
+
+    let result = client.try_withdraw_fees(&creator, &creator, &40);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_token_state_in_persistent_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &creator,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/1"),
+        &None,
+    );
+
+    env.as_contract(&client.address, || {
+        let instance = env.storage().instance();
+        assert!(instance.has(&DataKey::FactoryConfig));
+        assert!(!instance.has(&DataKey::CollectionInfo(collection_id)));
+        assert!(!instance.has(&DataKey::TokenOwner(collection_id, token_id)));
+        assert_eq!(instance.get_ttl(), INSTANCE_BUMP_AMOUNT);
+
+        let persistent = env.storage().persistent();
+        for key in [
+            DataKey::CollectionInfo(collection_id),
+            DataKey::TokenOwner(collection_id, token_id),
+            DataKey::TokenMetadata(collection_id, token_id),
+            DataKey::Balance(collection_id, creator.clone()),
+        ] {
+            assert!(persistent.has(&key));
+            assert_eq!(persistent.get_ttl(&key), PERSISTENT_BUMP_AMOUNT);
+        }
+    });
+}
+
+// Mints one token in a fresh Env loaded from `snapshot`, so that only the
+// entries the mint touches are in the footprint, and returns the CPU
+// instructions and memory bytes it consumed.
+fn measure_mint(
+    snapshot: impl Into<SnapshotSourceInput>,
+    contract_id: &Address,
+    collection_id: u64,
+    to: &Address,
+) -> (u64, u64) {
+    let env = Env::from_ledger_snapshot(snapshot);
+    env.mock_all_auths();
+
+    let contract_id = Address::try_from_val(&env, &ScAddress::from(contract_id)).unwrap();
+    let to = Address::try_from_val(&env, &ScAddress::from(to)).unwrap();
+    env.register_at(&contract_id, CollectionFactoryContract, ());
+
+    let client = CollectionFactoryContractClient::new(&env, &contract_id);
+    client.mint(
+        &collection_id,
+        &to,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/token"),
+        &None,
+    );
+
+    let budget = env.cost_estimate().budget();
+    (budget.cpu_instruction_cost(), budget.memory_bytes_cost())
+}
+
+// Builds the ledger entry for a persistent contract data key, as written by
+// the factory's storage layer.
+fn persistent_entry(
+    env: &Env,
+    contract_id: &Address,
+    key: DataKey,
+    val: Val,
+) -> (Box<LedgerKey>, (Box<LedgerEntry>, Option<u32>)) {
+    let contract = ScAddress::from(contract_id);
+    let key = ScVal::try_from_val(env, &IntoVal::<Env, Val>::into_val(&key, env)).unwrap();
+    let val = ScVal::try_from_val(env, &val).unwrap();
+
+    let ledger_key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: contract.clone(),
+        key: key.clone(),
+        durability: ContractDataDurability::Persistent,
+    });
+    let ledger_entry = LedgerEntry {
+        last_modified_ledger_seq: env.ledger().sequence(),
+        data: LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract,
+            key,
+            durability: ContractDataDurability::Persistent,
+            val,
+        }),
+        ext: LedgerEntryExt::V0,
+    };
+    let live_until = env.ledger().sequence() + PERSISTENT_BUMP_AMOUNT;
+
+    (
+        Box::new(ledger_key),
+        (Box::new(ledger_entry), Some(live_until)),
+    )
+}
+
+#[test]
+fn test_mint_budget_flat_as_collection_grows() {
+    const TOKENS: u32 = 10_000;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let token_id = client.mint(
+        &collection_id,
+        &creator,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/token"),
+        &None,
+    );
+
+    let (first_cpu, first_mem) = measure_mint(
+        env.to_ledger_snapshot(),
+        &client.address,
+        collection_id,
+        &creator,
+    );
+
+    // Grow the collection to 10k tokens. Minting them one by one is too slow
+    // in the test host, so the per-token entries are written straight into
+    // the ledger snapshot and the collection counters are updated to match.
+    env.cost_estimate().budget().reset_unlimited();
+    let metadata = client.token_metadata(&collection_id, &token_id);
+    env.as_contract(&client.address, || {
+        let mut info = <DataKey as Storage>::get_collection_info(&env, collection_id).unwrap();
+        info.total_tokens = TOKENS;
+        <DataKey as Storage>::set_collection_info(&env, collection_id, &info);
+        <DataKey as Storage>::set_next_token_id(&env, collection_id, TOKENS + 1);
+        <DataKey as Storage>::set_balance(&env, collection_id, &creator, TOKENS);
+    });
+
+    let mut snapshot = env.to_ledger_snapshot();
+    for id in (token_id + 1)..=TOKENS {
+        let token_metadata = TokenMetadata {
+            token_id: id,
+            ..metadata.clone()
+        };
+        snapshot.ledger_entries.push(persistent_entry(
+            &env,
+            &client.address,
+            DataKey::TokenOwner(collection_id, id),
+            creator.to_val(),
+        ));
+        snapshot.ledger_entries.push(persistent_entry(
+            &env,
+            &client.address,
+            DataKey::TokenMetadata(collection_id, id),
+            token_metadata.into_val(&env),
+        ));
+
+        let index = id - 1;
+        for (key, val) in [
+            (
+                DataKey::OwnerToken(collection_id, creator.clone(), index),
+                id,
+            ),
+            (DataKey::OwnerTokenIndex(collection_id, id), index),
+            (DataKey::TokenByIndex(collection_id, index), id),
+            (DataKey::TokenIndex(collection_id, id), index),
+        ] {
+            snapshot.ledger_entries.push(persistent_entry(
+                &env,
+                &client.address,
+                key,
+                val.into_val(&env),
+            ));
+        }
+    }
+
+    let (last_cpu, last_mem) = measure_mint(snapshot, &client.address, collection_id, &creator);
+
+    assert!(last_cpu <= first_cpu + first_cpu / 20);
+    assert!(last_mem <= first_mem + first_mem / 20);
+}
+
+#[test]
+fn test_token_enumeration_with_burns() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    for _ in 0..5 {
+        client.mint(&collection_id, &alice, &uri, &None);
+    }
+
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &alice, &0, &10),
+        vec![&env, 1, 2, 3, 4, 5]
+    );
+    assert_eq!(
+        client.tokens(&collection_id, &0, &10),
+        vec![&env, 1, 2, 3, 4, 5]
+    );
+    assert_eq!(client.tokens(&collection_id, &1, &2), vec![&env, 2, 3]);
+    assert_eq!(client.token_by_index(&collection_id, &4), 5);
+
+    // Burning from the middle moves the last token into the freed slot
+    client.burn(&collection_id, &alice, &2);
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &alice, &0, &10),
+        vec![&env, 1, 5, 3, 4]
+    );
+    assert_eq!(
+        client.tokens(&collection_id, &0, &10),
+        vec![&env, 1, 5, 3, 4]
+    );
+    assert_eq!(
+        client.try_token_by_index(&collection_id, &4),
+        Err(Ok(Error::TokenNotFound))
+    );
+
+    client.transfer(&collection_id, &alice, &bob, &1);
+    client.transfer(&collection_id, &alice, &alice, &4);
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &alice, &0, &10),
+        vec![&env, 3, 5, 4]
+    );
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &alice, &1, &1),
+        vec![&env, 5]
+    );
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &bob, &0, &10),
+        vec![&env, 1]
+    );
+
+    // Burning the last token of an owner and of the collection
+    client.burn(&collection_id, &bob, &1);
+    client.burn(&collection_id, &alice, &3);
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &bob, &0, &10).len(),
+        0
+    );
+    assert_eq!(
+        client.tokens_of_owner(&collection_id, &alice, &0, &10),
+        vec![&env, 4, 5]
+    );
+    assert_eq!(client.tokens(&collection_id, &0, &10), vec![&env, 4, 5]);
+    assert_eq!(client.tokens(&collection_id, &2, &10).len(), 0);
+
+    let token_id = client.mint(&collection_id, &bob, &uri, &None);
+    assert_eq!(
+        client.tokens(&collection_id, &0, &10),
+        vec![&env, 4, 5, token_id]
+    );
+    assert_eq!(client.token_by_index(&collection_id, &2), token_id);
+}
+
+#[test]
+fn test_collection_directory() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let first = create_test_collection(&env, &client, &alice);
+    let second = create_test_collection(&env, &client, &bob);
+    let third = create_test_collection(&env, &client, &alice);
+
+    assert_eq!(
+        client.list_collections(&0, &10, &None),
+        vec![&env, first, second, third]
+    );
+    assert_eq!(client.list_collections(&1, &1, &None), vec![&env, second]);
+    assert_eq!(client.list_collections(&3, &10, &None).len(), 0);
+
+    assert_eq!(
+        client.collections_by_creator(&alice, &0, &10, &None),
+        vec![&env, first, third]
+    );
+    assert_eq!(
+        client.collections_by_creator(&alice, &1, &10, &None),
+        vec![&env, third]
+    );
+    assert_eq!(
+        client.collections_by_creator(&bob, &0, &10, &None),
+        vec![&env, second]
+    );
+    assert_eq!(
+        client.collections_by_creator(&owner, &0, &10, &None).len(),
+        0
+    );
+
+    client.set_paused(&third, &alice, &true);
+    assert!(client.get_collection_info(&third).is_paused);
+
+    assert_eq!(
+        client.list_collections(&0, &10, &Some(CollectionStatus::Active)),
+        vec![&env, first, second]
+    );
+    assert_eq!(
+        client.list_collections(&0, &10, &Some(CollectionStatus::Paused)),
+        vec![&env, third]
+    );
+    assert_eq!(
+        client.collections_by_creator(&alice, &0, &10, &Some(CollectionStatus::Active)),
+        vec![&env, first]
+    );
+}
+
+#[test]
+fn test_token_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let uris = vec![&env, uri.clone(), uri.clone()];
+    client.batch_mint(&collection_id, &alice, &uris, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [
+            events::Mint {
+                collection_id,
+                version: EVENT_VERSION,
+                to: alice.clone(),
+                token_id: 1,
+            }
+            .to_xdr(&env, &client.address),
+            events::Mint {
+                collection_id,
+                version: EVENT_VERSION,
+                to: alice.clone(),
+                token_id: 2,
+            }
+            .to_xdr(&env, &client.address),
+        ]
+    );
+
+    client.approve(&collection_id, &alice, &bob, &1, &100);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::Approve {
+            collection_id,
+            version: EVENT_VERSION,
+            owner: alice.clone(),
+            approved: bob.clone(),
+            token_id: 1,
+            live_until_ledger: 100,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    client.transfer_from(&collection_id, &bob, &alice, &bob, &1);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::Transfer {
+            collection_id,
+            version: EVENT_VERSION,
+            from: alice.clone(),
+            to: bob.clone(),
+            token_id: 1,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    client.set_approval_for_all(&collection_id, &alice, &bob, &100);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::ApproveForAll {
+            collection_id,
+            version: EVENT_VERSION,
+            owner: alice.clone(),
+            operator: bob.clone(),
+            live_until_ledger: 100,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    client.burn(&collection_id, &alice, &2);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::Burn {
+            collection_id,
+            version: EVENT_VERSION,
+            from: alice.clone(),
+            token_id: 2,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+}
+
+#[test]
+fn test_collection_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+
+    client.set_royalty_info(&collection_id, &creator, &bob, &750);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::RoyaltyUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            recipient: bob.clone(),
+            percentage: 750,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    client.set_whitelist(&collection_id, &creator, &alice, &true);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::WhitelistUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            address: alice.clone(),
+            whitelisted: true,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    client.set_paused(&collection_id, &creator, &true);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::PauseUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            paused: true,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+}
+
+#[test]
+fn test_factory_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let contract_id = env.register(CollectionFactoryContract, ());
+    let client = CollectionFactoryContractClient::new(&env, &contract_id);
+
+    client.initialize(&owner);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::FactoryInitialized {
+            version: EVENT_VERSION,
+            owner: owner.clone(),
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    let wasm_hash = upload_collection_wasm(&env);
+    client.set_collection_wasm_hash(&owner, &wasm_hash);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::CollectionWasmUpdated {
+            version: EVENT_VERSION,
+            wasm_hash,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::CollectionCreated {
+            collection_id,
+            version: EVENT_VERSION,
+            creator: creator.clone(),
+            address: client.get_collection_address(&collection_id),
+            fee: 0,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    let token_admin = Address::generate(&env);
+    let fee_token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    client.set_fee_token(&owner, &fee_token);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::FeeTokenUpdated {
+            version: EVENT_VERSION,
+            fee_token: fee_token.clone(),
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    client.set_factory_fee(&owner, &100);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::FeeUpdated {
+            version: EVENT_VERSION,
+            old_fee: 0,
+            new_fee: 100,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    token::StellarAssetClient::new(&env, &fee_token).mint(&creator, &100);
+    create_test_collection(&env, &client, &creator);
+    client.withdraw_fees(&owner, &owner, &100);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::FeesWithdrawn {
+            version: EVENT_VERSION,
+            recipient: owner.clone(),
+            amount: 100,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    client.set_max_collections(&owner, &Some(10));
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::MaxCollectionsUpdated {
+            version: EVENT_VERSION,
+            max_collections: Some(10),
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+
+    client.set_factory_active(&owner, &false);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::FactoryActiveUpdated {
+            version: EVENT_VERSION,
+            active: false,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+}
+
+#[test]
+fn test_two_step_factory_ownership() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let contract_id = env.register(CollectionFactoryContract, ());
+    let client = CollectionFactoryContractClient::new(&env, &contract_id);
+    client.initialize(&owner);
+
+    assert_eq!(
+        client.try_accept_owner(&new_owner),
+        Err(Ok(Error::NoPendingTransfer))
+    );
+    assert_eq!(
+        client.try_propose_owner(&stranger, &new_owner, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_propose_owner(&owner, &new_owner, &env.ledger().sequence()),
+        Err(Ok(Error::InvalidInput))
+    );
+
+    // A cancelled offer can no longer be accepted
+    client.propose_owner(&owner, &new_owner, &100);
+    client.cancel_owner_proposal(&owner);
+    assert_eq!(client.get_pending_owner(), None);
+    assert_eq!(
+        client.try_accept_owner(&new_owner),
+        Err(Ok(Error::NoPendingTransfer))
+    );
+
+    // An expired offer can no longer be accepted
+    client.propose_owner(&owner, &new_owner, &100);
+    env.ledger().set_sequence_number(101);
+    assert_eq!(
+        client.try_accept_owner(&new_owner),
+        Err(Ok(Error::TransferOfferExpired))
+    );
+
+    client.propose_owner(&owner, &new_owner, &200);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::OwnerProposed {
+            version: EVENT_VERSION,
+            owner: owner.clone(),
+            new_owner: new_owner.clone(),
+            expiration_ledger: 200,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+    assert_eq!(
+        client.try_accept_owner(&stranger),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.accept_owner(&new_owner);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::OwnerTransferred {
+            version: EVENT_VERSION,
+            old_owner: owner.clone(),
+            new_owner: new_owner.clone(),
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+    assert_eq!(client.get_factory_config().owner, new_owner);
+    assert_eq!(client.get_pending_owner(), None);
+
+    assert_eq!(
+        client.try_set_factory_fee(&owner, &10),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_factory_fee(&new_owner, &10);
+}
+
+#[test]
+fn test_two_step_collection_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    assert_eq!(client.get_collection_info(&collection_id).admin, creator);
+
+    assert_eq!(
+        client.try_propose_collection_admin(&collection_id, &new_admin, &new_admin, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.propose_collection_admin(&collection_id, &creator, &new_admin, &100);
+    client.cancel_collection_admin_proposal(&collection_id, &creator);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::CollectionAdminCancelled {
+            collection_id,
+            version: EVENT_VERSION,
+            new_admin: new_admin.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(
+        client.try_accept_collection_admin(&collection_id, &new_admin),
+        Err(Ok(Error::NoPendingTransfer))
+    );
+
+    client.propose_collection_admin(&collection_id, &creator, &new_admin, &100);
+    env.ledger().set_sequence_number(101);
+    assert_eq!(
+        client.try_accept_collection_admin(&collection_id, &new_admin),
+        Err(Ok(Error::TransferOfferExpired))
+    );
+
+    client.propose_collection_admin(&collection_id, &creator, &new_admin, &200);
+    client.accept_collection_admin(&collection_id, &new_admin);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::CollectionAdminTransferred {
+            collection_id,
+            version: EVENT_VERSION,
+            old_admin: creator.clone(),
+            new_admin: new_admin.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    // The creator is kept for the directory, but admin rights move over
+    let info = client.get_collection_info(&collection_id);
+    assert_eq!(info.creator, creator);
+    assert_eq!(info.admin, new_admin);
+    assert_eq!(client.get_pending_collection_admin(&collection_id), None);
+
+    assert_eq!(
+        client.try_set_paused(&collection_id, &creator, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_royalty_info(&collection_id, &creator, &creator, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_paused(&collection_id, &new_admin, &true);
+    client.set_whitelist(&collection_id, &new_admin, &creator, &true);
+    client.set_royalty_info(&collection_id, &new_admin, &new_admin, &100);
+}
+
+#[test]
+fn test_grant_and_revoke_roles() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let collection_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Private Collection"),
+        &String::from_str(&env, "PRIV"),
+        &String::from_str(&env, "Minted by the studio only"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &false,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    assert_eq!(
+        client.try_grant_role(&collection_id, &minter, &Role::Minter, &minter),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_minter_mint(&collection_id, &minter, &user, &uri, &None),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.grant_role(&collection_id, &creator, &Role::Minter, &minter);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::RoleGranted {
+            collection_id,
+            version: EVENT_VERSION,
+            role: Role::Minter,
+            account: minter.clone(),
+            sender: creator.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    client.grant_role(&collection_id, &creator, &Role::Pauser, &pauser);
+    client.grant_role(&collection_id, &creator, &Role::Pauser, &minter);
+
+    assert!(client.has_role(&collection_id, &Role::Minter, &minter));
+    assert!(!client.has_role(&collection_id, &Role::Minter, &pauser));
+    assert_eq!(
+        client.role_members(&collection_id, &Role::Pauser, &0, &10),
+        vec![&env, pauser.clone(), minter.clone()]
+    );
+    assert_eq!(
+        client.role_members(&collection_id, &Role::Pauser, &1, &10),
+        vec![&env, minter.clone()]
+    );
+
+    client.revoke_role(&collection_id, &creator, &Role::Pauser, &pauser);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::RoleRevoked {
+            collection_id,
+            version: EVENT_VERSION,
+            role: Role::Pauser,
+            account: pauser.clone(),
+            sender: creator.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert!(!client.has_role(&collection_id, &Role::Pauser, &pauser));
+    assert_eq!(
+        client.role_members(&collection_id, &Role::Pauser, &0, &10),
+        vec![&env, minter.clone()]
+    );
+    assert_eq!(
+        client.try_set_paused(&collection_id, &pauser, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // The admin keeps every permission without holding the roles
+    client.set_paused(&collection_id, &creator, &true);
+}
+
+#[test]
+fn test_role_permissions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let pauser = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let collection_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Private Collection"),
+        &String::from_str(&env, "PRIV"),
+        &String::from_str(&env, "Minted by the studio only"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &false,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    client.grant_role(&collection_id, &creator, &Role::Minter, &minter);
+    client.grant_role(&collection_id, &creator, &Role::Pauser, &pauser);
+
+    // A minter mints to users of a non-public collection without whitelisting
+    assert_eq!(
+        client.try_mint(&collection_id, &user, &uri, &None),
+        Err(Ok(Error::WhitelistRequired))
+    );
+    let token_id = client.minter_mint(&collection_id, &minter, &user, &uri, &None);
+    assert_eq!(client.owner_of(&collection_id, &token_id), user);
+
+    // Each role only grants its own permission
+    assert_eq!(
+        client.try_set_royalty_info(&collection_id, &pauser, &pauser, &100),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_whitelist(&collection_id, &minter, &user, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_paused(&collection_id, &pauser, &true);
+    assert_eq!(
+        client.try_minter_mint(&collection_id, &minter, &user, &uri, &None),
+        Err(Ok(Error::MintingPaused))
+    );
+    client.set_paused(&collection_id, &pauser, &false);
+
+    client.grant_role(&collection_id, &creator, &Role::RoyaltyAdmin, &pauser);
+    client.grant_role(&collection_id, &creator, &Role::WhitelistManager, &minter);
+    client.set_royalty_info(&collection_id, &pauser, &pauser, &100);
+    client.set_whitelist(&collection_id, &minter, &user, &true);
+    client.mint(&collection_id, &user, &uri, &None);
+}
+
+#[test]
+fn test_mint_with_merkle_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let dave = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let collection_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Allowlist Collection"),
+        &String::from_str(&env, "ALLOW"),
+        &String::from_str(&env, "Minted from an allowlist"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &false,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let leaves = [
+        merkle::leaf(&env, &alice, 2),
+        merkle::leaf(&env, &bob, 1),
+        merkle::leaf(&env, &carol, 1),
+        merkle::leaf(&env, &dave, 3),
+    ];
+    let left = merkle::hash_pair(&env, &leaves[0], &leaves[1]);
+    let right = merkle::hash_pair(&env, &leaves[2], &leaves[3]);
+    let root = merkle::hash_pair(&env, &left, &right);
+
+    let alice_proof = vec![&env, leaves[1].clone(), right.clone()];
+    let carol_proof = vec![&env, leaves[3].clone(), left.clone()];
+
+    assert_eq!(
+        client.try_mint_with_proof(&collection_id, &alice, &uri, &None, &alice_proof, &2),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_set_merkle_root(&collection_id, &alice, &0, &root),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_merkle_root(&collection_id, &creator, &0, &root);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::MerkleRootUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            phase: 0,
+            root: root.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.merkle_root(&collection_id, &0), Some(root));
+
+    // Alice may mint up to her allowance of 2
+    client.mint_with_proof(&collection_id, &alice, &uri, &None, &alice_proof, &2);
+    client.mint_with_proof(&collection_id, &alice, &uri, &None, &alice_proof, &2);
+    assert_eq!(client.allowlist_minted(&collection_id, &0, &alice), 2);
+    assert_eq!(client.balance_of(&collection_id, &alice), 2);
+    assert_eq!(
+        client.try_mint_with_proof(&collection_id, &alice, &uri, &None, &alice_proof, &2),
+        Err(Ok(Error::AllowanceExceeded))
+    );
+
+    // Claiming a larger allowance, or another member's proof, fails
+    assert_eq!(
+        client.try_mint_with_proof(&collection_id, &alice, &uri, &None, &alice_proof, &3),
+        Err(Ok(Error::InvalidProof))
+    );
+    assert_eq!(
+        client.try_mint_with_proof(&collection_id, &bob, &uri, &None, &carol_proof, &1),
+        Err(Ok(Error::InvalidProof))
+    );
+
+    client.mint_with_proof(&collection_id, &carol, &uri, &None, &carol_proof, &1);
+    assert_eq!(client.balance_of(&collection_id, &carol), 1);
+
+    // Non-members still need the per-address whitelist
+    assert_eq!(
+        client.try_mint(&collection_id, &bob, &uri, &None),
+        Err(Ok(Error::WhitelistRequired))
+    );
+    client.set_whitelist(&collection_id, &creator, &bob, &true);
+    client.mint(&collection_id, &bob, &uri, &None);
+}
+
+#[test]
+fn test_mint_with_proof_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+    let collection_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Allowlist Collection"),
+        &String::from_str(&env, "ALLOW"),
+        &String::from_str(&env, "Minted from an allowlist"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &false,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &false,
+        &0,
+    );
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let alice_leaf = merkle::leaf(&env, &alice, 1);
+    let bob_leaf = merkle::leaf(&env, &bob, 1);
+    client.set_merkle_root(
+        &collection_id,
+        &creator,
+        &0,
+        &merkle::hash_pair(&env, &alice_leaf, &bob_leaf),
+    );
+
+    // Allowlisted wallets mint from the collection contract with their proof
+    let token_id = collection.mint_with_proof(&alice, &uri, &None, &vec![&env, bob_leaf], &1);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), alice.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(collection.owner_of(&token_id), alice);
+    assert_eq!(client.allowlist_minted(&collection_id, &0, &alice), 1);
+
+    // The factory's allowlist rules apply unchanged
+    assert_eq!(
+        collection.try_mint_with_proof(&alice, &uri, &None, &vec![&env, alice_leaf.clone()], &1),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::InvalidProof as u32
+        )))
+    );
+    collection.mint_with_proof(&bob, &uri, &None, &vec![&env, alice_leaf], &1);
+    assert_eq!(collection.total_supply(), 2);
+}
+
+#[test]
+fn test_set_mint_phases_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(500);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    for buyer in [&alice, &bob, &carol] {
+        token::StellarAssetClient::new(&env, &sac.address()).mint(buyer, &1_000);
+    }
+    client.set_fee_token(&owner, &sac.address());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+
+    let presale = MintPhase {
+        start_time: 1_000,
+        end_time: 2_000,
+        price: 50,
+        price_token: Some(sac.address()),
+        wallet_limit: Some(1),
+        supply_cap: Some(2),
+        requires_allowlist: true,
+    };
+    let public_sale = MintPhase {
+        start_time: 2_500,
+        end_time: 3_000,
+        price: 100,
+        price_token: Some(sac.address()),
+        wallet_limit: Some(2),
+        supply_cap: None,
+        requires_allowlist: false,
+    };
+
+    // Overlapping or unpriced phases are rejected
+    let overlapping = MintPhase {
+        start_time: 1_500,
+        ..public_sale.clone()
+    };
+    assert_eq!(
+        client.try_set_mint_phases(
+            &collection_id,
+            &creator,
+            &vec![&env, presale.clone(), overlapping]
+        ),
+        Err(Ok(Error::InvalidConfig))
+    );
+    let unpriced = MintPhase {
+        price_token: None,
+        ..public_sale.clone()
+    };
+    assert_eq!(
+        client.try_set_mint_phases(&collection_id, &creator, &vec![&env, unpriced]),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_mint_phases(&collection_id, &alice, &vec![&env, presale.clone()]),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    client.set_mint_phases(
+        &collection_id,
+        &creator,
+        &vec![&env, presale.clone(), public_sale.clone()],
+    );
+    assert_eq!(client.mint_phases(&collection_id).len(), 2);
+}
+
+#[test]
+fn test_mint_phases() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(500);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let payment_token = token::Client::new(&env, &sac.address());
+    for buyer in [&alice, &bob, &carol] {
+        token::StellarAssetClient::new(&env, &sac.address()).mint(buyer, &1_000);
+    }
+    client.set_fee_token(&owner, &sac.address());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let presale = MintPhase {
+        start_time: 1_000,
+        end_time: 2_000,
+        price: 50,
+        price_token: Some(sac.address()),
+        wallet_limit: Some(1),
+        supply_cap: Some(2),
+        requires_allowlist: true,
+    };
+    let public_sale = MintPhase {
+        start_time: 2_500,
+        end_time: 3_000,
+        price: 100,
+        price_token: Some(sac.address()),
+        wallet_limit: Some(2),
+        supply_cap: None,
+        requires_allowlist: false,
+    };
+
+    client.set_mint_phases(&collection_id, &creator, &vec![&env, presale, public_sale]);
+
+    // Before the first phase
+    assert_eq!(client.active_phase(&collection_id), None);
+    assert_eq!(
+        client.try_mint(&collection_id, &alice, &uri, &None),
+        Err(Ok(Error::NoActivePhase))
+    );
+
+    // Presale: allowlist only, one per wallet, two in total
+    env.ledger().set_timestamp(1_000);
+    assert_eq!(client.active_phase(&collection_id), Some(1));
+    assert_eq!(
+        client.try_mint(&collection_id, &alice, &uri, &None),
+        Err(Ok(Error::WhitelistRequired))
+    );
+    for buyer in [&alice, &bob, &carol] {
+        client.set_whitelist(&collection_id, &creator, buyer, &true);
+    }
+    client.mint(&collection_id, &alice, &uri, &None);
+    assert_eq!(payment_token.balance(&alice), 950);
+    assert_eq!(payment_token.balance(&creator), 50);
+    assert_eq!(
+        client.try_mint(&collection_id, &alice, &uri, &None),
+        Err(Ok(Error::WalletLimitReached))
+    );
+    client.mint(&collection_id, &bob, &uri, &None);
+    assert_eq!(
+        client.try_mint(&collection_id, &carol, &uri, &None),
+        Err(Ok(Error::MaxSupplyExceeded))
+    );
+
+    // Between phases
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        client.try_mint(&collection_id, &carol, &uri, &None),
+        Err(Ok(Error::NoActivePhase))
+    );
+
+    // Public sale: open to everyone, two per wallet
+    env.ledger().set_timestamp(2_500);
+    let dave = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&dave, &1_000);
+    let uris = vec![&env, uri.clone(), uri.clone()];
+    client.batch_mint(&collection_id, &dave, &uris, &None);
+    assert_eq!(payment_token.balance(&dave), 800);
+    assert_eq!(payment_token.balance(&creator), 300);
+    assert_eq!(
+        client.try_mint(&collection_id, &dave, &uri, &None),
+        Err(Ok(Error::WalletLimitReached))
+    );
+
+    // Minters are not bound by the schedule
+    env.ledger().set_timestamp(5_000);
+    client.minter_mint(&collection_id, &creator, &carol, &uri, &None);
+    assert_eq!(client.total_supply(&collection_id), 5);
+}
+
+#[test]
+fn test_mint_phases_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(500);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    client.set_mint_phases(
+        &collection_id,
+        &creator,
+        &vec![
+            &env,
+            MintPhase {
+                start_time: 1_000,
+                end_time: 2_000,
+                price: 0,
+                price_token: None,
+                wallet_limit: Some(1),
+                supply_cap: None,
+                requires_allowlist: false,
+            },
+        ],
+    );
+
+    // Mints through the collection contract follow the schedule too
+    assert_eq!(
+        collection.try_mint(&alice, &uri, &None),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::NoActivePhase as u32
+        )))
+    );
+
+    env.ledger().set_timestamp(1_000);
+    let token_id = collection.mint(&alice, &uri, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), alice.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(client.active_phase(&collection_id), Some(1));
+    assert_eq!(
+        collection.try_mint(&alice, &uri, &None),
+        Err(Ok(soroban_sdk::Error::from_contract_error(
+            Error::WalletLimitReached as u32
+        )))
+    );
+}
+
+#[test]
+fn test_new_mint_schedule_resets_phase_counts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(500);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    for buyer in [&alice, &bob, &carol] {
+        token::StellarAssetClient::new(&env, &sac.address()).mint(buyer, &1_000);
+    }
+    client.set_fee_token(&owner, &sac.address());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let presale = MintPhase {
+        start_time: 1_000,
+        end_time: 2_000,
+        price: 50,
+        price_token: Some(sac.address()),
+        wallet_limit: Some(1),
+        supply_cap: Some(2),
+        requires_allowlist: true,
+    };
+
+    client.set_mint_phases(&collection_id, &creator, &vec![&env, presale.clone()]);
+    env.ledger().set_timestamp(1_000);
+    for buyer in [&alice, &bob] {
+        client.set_whitelist(&collection_id, &creator, buyer, &true);
+        client.mint(&collection_id, buyer, &uri, &None);
+    }
+    let dave = Address::generate(&env);
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&dave, &1_000);
+    env.ledger().set_timestamp(5_000);
+
+    // A new schedule counts from zero, even for a phase ID used before
+    let encore = MintPhase {
+        start_time: 5_000,
+        end_time: 6_000,
+        requires_allowlist: false,
+        ..presale.clone()
+    };
+    client.set_mint_phases(&collection_id, &creator, &vec![&env, encore]);
+    assert_eq!(client.active_phase(&collection_id), Some(1));
+    client.mint(&collection_id, &alice, &uri, &None);
+    assert_eq!(
+        client.try_mint(&collection_id, &alice, &uri, &None),
+        Err(Ok(Error::WalletLimitReached))
+    );
+    client.mint(&collection_id, &carol, &uri, &None);
+    assert_eq!(
+        client.try_mint(&collection_id, &dave, &uri, &None),
+        Err(Ok(Error::MaxSupplyExceeded))
+    );
+}
+
+#[test]
+fn test_mint_with_proof_in_phase() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let presale = MintPhase {
+        start_time: 1_000,
+        end_time: 2_000,
+        price: 0,
+        price_token: None,
+        wallet_limit: None,
+        supply_cap: None,
+        requires_allowlist: true,
+    };
+    client.set_mint_phases(&collection_id, &creator, &vec![&env, presale.clone()]);
+
+    let alice_leaf = merkle::leaf(&env, &alice, 1);
+    let bob_leaf = merkle::leaf(&env, &bob, 1);
+    let root = merkle::hash_pair(&env, &alice_leaf, &bob_leaf);
+
+    // Roots are per phase: the default phase root does not open phase 1
+    client.set_merkle_root(&collection_id, &creator, &0, &root);
+    assert_eq!(
+        client.try_mint_with_proof(
+            &collection_id,
+            &alice,
+            &uri,
+            &None,
+            &vec![&env, bob_leaf.clone()],
+            &1
+        ),
+        Err(Ok(Error::InvalidProof))
+    );
+
+    client.set_merkle_root(&collection_id, &creator, &1, &root);
+    client.mint_with_proof(
+        &collection_id,
+        &alice,
+        &uri,
+        &None,
+        &vec![&env, bob_leaf.clone()],
+        &1,
+    );
+    assert_eq!(client.allowlist_minted(&collection_id, &1, &alice), 1);
+    assert_eq!(
+        client.try_mint(&collection_id, &bob, &uri, &None),
+        Err(Ok(Error::WhitelistRequired))
+    );
+    client.mint_with_proof(
+        &collection_id,
+        &bob,
+        &uri,
+        &None,
+        &vec![&env, alice_leaf.clone()],
+        &1,
+    );
+}
+
+#[test]
+fn test_new_mint_schedule_drops_allowlist_roots() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let presale = MintPhase {
+        start_time: 1_000,
+        end_time: 2_000,
+        price: 0,
+        price_token: None,
+        wallet_limit: None,
+        supply_cap: None,
+        requires_allowlist: true,
+    };
+    client.set_mint_phases(&collection_id, &creator, &vec![&env, presale.clone()]);
+
+    let alice_leaf = merkle::leaf(&env, &alice, 1);
+    let bob_leaf = merkle::leaf(&env, &bob, 1);
+    let root = merkle::hash_pair(&env, &alice_leaf, &bob_leaf);
+
+    client.set_merkle_root(&collection_id, &creator, &1, &root);
+    client.mint_with_proof(
+        &collection_id,
+        &alice,
+        &uri,
+        &None,
+        &vec![&env, bob_leaf.clone()],
+        &1,
+    );
+    assert_eq!(client.allowlist_minted(&collection_id, &1, &alice), 1);
+
+    // A new schedule starts without roots or allowlist counts
+    client.set_mint_phases(&collection_id, &creator, &vec![&env, presale]);
+    assert_eq!(client.merkle_root(&collection_id, &1), None);
+    assert_eq!(client.allowlist_minted(&collection_id, &1, &alice), 0);
+    assert_eq!(
+        client.try_mint_with_proof(
+            &collection_id,
+            &alice,
+            &uri,
+            &None,
+            &vec![&env, bob_leaf.clone()],
+            &1
+        ),
+        Err(Ok(Error::InvalidProof))
+    );
+    client.set_merkle_root(&collection_id, &creator, &1, &root);
+    client.mint_with_proof(
+        &collection_id,
+        &alice,
+        &uri,
+        &None,
+        &vec![&env, bob_leaf],
+        &1,
+    );
+    assert_eq!(client.allowlist_minted(&collection_id, &1, &alice), 1);
+}
+
+#[test]
+fn test_mint_price_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let price = MintPrice {
+        token: sac.address(),
+        amount: 200,
+    };
+
+    // Prices must be in the factory fee token
+    assert_eq!(
+        client.try_set_mint_price(&collection_id, &creator, &Some(price.clone())),
+        Err(Ok(Error::FeeTokenNotSet))
+    );
+    client.set_fee_token(&owner, &sac.address());
+    let other_token = env
+        .register_stellar_asset_contract_v2(owner.clone())
+        .address();
+    assert_eq!(
+        client.try_set_mint_price(
+            &collection_id,
+            &creator,
+            &Some(MintPrice {
+                token: other_token,
+                amount: 200,
+            })
+        ),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_platform_fee_bps(&owner, &10_001),
+        Err(Ok(Error::InvalidInput))
+    );
+}
+
+#[test]
+fn test_paid_mint_splits_proceeds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let payment_token = token::Client::new(&env, &sac.address());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let price = MintPrice {
+        token: sac.address(),
+        amount: 200,
+    };
+
+    client.set_fee_token(&owner, &sac.address());
+
+    client.set_mint_price(&collection_id, &creator, &Some(price.clone()));
+    client.set_platform_fee_bps(&owner, &250);
+    assert_eq!(client.mint_price(&collection_id), Some(price));
+
+    client.mint(&collection_id, &buyer, &uri, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [
+            events::Mint {
+                collection_id,
+                version: EVENT_VERSION,
+                to: buyer.clone(),
+                token_id: 1,
+            }
+            .to_xdr(&env, &client.address),
+            events::MintPaid {
+                collection_id,
+                version: EVENT_VERSION,
+                payer: buyer.clone(),
+                token: sac.address(),
+                amount: 200,
+                platform_share: 5,
+            }
+            .to_xdr(&env, &client.address),
+        ]
+    );
+
+    let uris = vec![&env, uri.clone(), uri.clone()];
+    client.batch_mint(&collection_id, &buyer, &uris, &None);
+
+    assert_eq!(payment_token.balance(&buyer), 400);
+    assert_eq!(payment_token.balance(&creator), 585);
+    assert_eq!(payment_token.balance(&client.address), 15);
+    assert_eq!(client.get_factory_config().accumulated_fees, 15);
+
+    // The platform share is paid out through withdraw_fees
+    client.withdraw_fees(&owner, &treasury, &15);
+    assert_eq!(payment_token.balance(&treasury), 15);
+    assert_eq!(client.get_factory_config().accumulated_fees, 0);
+
+    // Removing the price makes minting free again
+    client.set_mint_price(&collection_id, &creator, &None);
+    client.mint(&collection_id, &buyer, &uri, &None);
+    assert_eq!(payment_token.balance(&buyer), 400);
+}
+
+#[test]
+fn test_mint_proceeds_go_to_creator_after_admin_handover() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let payment_token = token::Client::new(&env, &sac.address());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+    client.set_fee_token(&owner, &sac.address());
+
+    client.propose_collection_admin(&collection_id, &creator, &new_admin, &100);
+    client.accept_collection_admin(&collection_id, &new_admin);
+
+    // The admin runs the sale, but the creator keeps the proceeds
+    client.set_mint_price(
+        &collection_id,
+        &new_admin,
+        &Some(MintPrice {
+            token: sac.address(),
+            amount: 200,
+        }),
+    );
+    client.mint(
+        &collection_id,
+        &buyer,
+        &String::from_str(&env, "https://api.nftopia.com/metadata/token"),
+        &None,
+    );
+    assert_eq!(payment_token.balance(&creator), 200);
+    assert_eq!(payment_token.balance(&new_admin), 0);
+}
+
+#[test]
+fn test_paid_mint_through_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+    let payment_token = token::Client::new(&env, &sac.address());
+    token::StellarAssetClient::new(&env, &sac.address()).mint(&buyer, &1_000);
+    client.set_fee_token(&owner, &sac.address());
+    client.set_platform_fee_bps(&owner, &250);
+    client.set_mint_price(
+        &collection_id,
+        &creator,
+        &Some(MintPrice {
+            token: sac.address(),
+            amount: 200,
+        }),
+    );
+
+    // The buyer pays the same price minting through the collection contract
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let token_id = collection.mint(&buyer, &uri, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&collection.address),
+        vec![
+            &env,
+            (
+                collection.address.clone(),
+                (Symbol::new(&env, "mint"), buyer.clone()).into_val(&env),
+                token_id.into_val(&env),
+            )
+        ]
+    );
+    assert_eq!(payment_token.balance(&buyer), 800);
+    assert_eq!(payment_token.balance(&creator), 195);
+    assert_eq!(payment_token.balance(&client.address), 5);
+
+    let uris = vec![&env, uri.clone(), uri];
+    collection.batch_mint(&buyer, &uris, &None);
+    assert_eq!(payment_token.balance(&buyer), 400);
+    assert_eq!(client.get_factory_config().accumulated_fees, 15);
+}
+
+#[test]
+fn test_fee_token_fixed_while_collections_charge() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let sac = env.register_stellar_asset_contract_v2(owner.clone());
+
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let price = MintPrice {
+        token: sac.address(),
+        amount: 200,
+    };
+
+    client.set_fee_token(&owner, &sac.address());
+    client.set_mint_price(&collection_id, &creator, &Some(price));
+
+    // The fee token stays put while any collection charges in it
+    let next_token = env
+        .register_stellar_asset_contract_v2(owner.clone())
+        .address();
+    assert_eq!(
+        client.try_set_fee_token(&owner, &next_token),
+        Err(Ok(Error::InvalidConfig))
+    );
+
+    client.set_mint_price(&collection_id, &creator, &None);
+
+    // A priced phase holds the fee token just like a flat price
+    client.set_mint_phases(
+        &collection_id,
+        &creator,
+        &vec![
+            &env,
+            MintPhase {
+                start_time: 0,
+                end_time: 1_000,
+                price: 100,
+                price_token: Some(sac.address()),
+                wallet_limit: None,
+                supply_cap: None,
+                requires_allowlist: false,
+            },
+        ],
+    );
+    assert_eq!(
+        client.try_set_fee_token(&owner, &next_token),
+        Err(Ok(Error::InvalidConfig))
+    );
+    client.set_mint_phases(&collection_id, &creator, &vec![&env]);
+    client.set_fee_token(&owner, &next_token);
+}
+
+#[test]
+fn test_update_token_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let collection_id = create_collection_with_schema(&env, &client, &creator, 1);
+    client.set_attribute_schema(
+        &collection_id,
+        &creator,
+        &vec![
+            &env,
+            AttributeDefinition {
+                key: String::from_str(&env, "rarity"),
+                value_type: AttributeType::Text,
+                required: false,
+            },
+        ],
+    );
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let first = client.mint(&collection_id, &holder, &uri, &None);
+
+    let new_uri = String::from_str(&env, "https://api.nftopia.com/metadata/revised");
+    let mut attributes = Map::new(&env);
+    attributes.set(
+        String::from_str(&env, "rarity"),
+        String::from_str(&env, "legendary"),
+    );
+
+    assert_eq!(
+        client.try_update_token_metadata(&collection_id, &editor, &first, &new_uri, &attributes),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.grant_role(&collection_id, &creator, &Role::MetadataEditor, &editor);
+
+    env.ledger().set_timestamp(2_000);
+    client.update_token_metadata(&collection_id, &editor, &first, &new_uri, &attributes);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::MetadataUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            token_id: Some(first),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    let metadata = client.token_metadata(&collection_id, &first);
+    assert_eq!(metadata.uri, new_uri);
+    assert_eq!(metadata.attributes, attributes);
+    assert_eq!(metadata.created_at, 1_000);
+    assert_eq!(metadata.updated_at, Some(2_000));
+
+    let base_uri = String::from_str(&env, "ipfs://collection/");
+    client.set_base_uri(&collection_id, &editor, &base_uri);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::MetadataUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            token_id: None,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(
+        client.get_collection_info(&collection_id).config.base_uri,
+        base_uri
+    );
+}
+
+#[test]
+fn test_freeze_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let collection_id = create_collection_with_schema(&env, &client, &creator, 1);
+    client.set_attribute_schema(
+        &collection_id,
+        &creator,
+        &vec![
+            &env,
+            AttributeDefinition {
+                key: String::from_str(&env, "rarity"),
+                value_type: AttributeType::Text,
+                required: false,
+            },
+        ],
+    );
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let first = client.mint(&collection_id, &holder, &uri, &None);
+    let second = client.mint(&collection_id, &holder, &uri, &None);
+
+    let new_uri = String::from_str(&env, "https://api.nftopia.com/metadata/revised");
+    let mut attributes = Map::new(&env);
+    attributes.set(
+        String::from_str(&env, "rarity"),
+        String::from_str(&env, "legendary"),
+    );
+
+    let base_uri = String::from_str(&env, "ipfs://collection/");
+    client.grant_role(&collection_id, &creator, &Role::MetadataEditor, &editor);
+
+    // Freezing one token blocks that token only
+    assert_eq!(
+        client.try_freeze_metadata(&collection_id, &holder, &Some(first)),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_freeze_metadata(&collection_id, &editor, &Some(99)),
+        Err(Ok(Error::TokenNotFound))
+    );
+    client.freeze_metadata(&collection_id, &editor, &Some(first));
+    assert!(client.is_metadata_frozen(&collection_id, &Some(first)));
+    assert!(!client.is_metadata_frozen(&collection_id, &None));
+    assert_eq!(
+        client.try_update_token_metadata(&collection_id, &creator, &first, &uri, &attributes),
+        Err(Ok(Error::MetadataFrozen))
+    );
+    client.update_token_metadata(&collection_id, &creator, &second, &new_uri, &attributes);
+
+    // A collection-wide freeze blocks every token and the base URI
+    client.freeze_metadata(&collection_id, &creator, &None);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::MetadataFrozen {
+            collection_id,
+            version: EVENT_VERSION,
+            token_id: None,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert!(client.is_metadata_frozen(&collection_id, &Some(second)));
+    assert_eq!(
+        client.try_update_token_metadata(&collection_id, &creator, &second, &uri, &attributes),
+        Err(Ok(Error::MetadataFrozen))
+    );
+    assert_eq!(
+        client.try_set_base_uri(&collection_id, &creator, &base_uri),
+        Err(Ok(Error::MetadataFrozen))
+    );
+}
+
+#[test]
+fn test_set_attribute_schema() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let definitions = vec![
+        &env,
+        AttributeDefinition {
+            key: String::from_str(&env, "rarity"),
+            value_type: AttributeType::Enum(vec![
+                &env,
+                String::from_str(&env, "common"),
+                String::from_str(&env, "rare"),
+            ]),
+            required: true,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "level"),
+            value_type: AttributeType::Integer,
+            required: false,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "weight"),
+            value_type: AttributeType::Decimal,
+            required: false,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "born"),
+            value_type: AttributeType::Timestamp,
+            required: false,
+        },
+    ];
+
+    // Metadata editors set the attribute schema, which must be well formed
+    let extended = create_collection_with_schema(&env, &client, &creator, 1);
+    assert_eq!(
+        client.try_set_attribute_schema(&extended, &holder, &definitions),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.grant_role(&extended, &creator, &Role::MetadataEditor, &editor);
+    let mut duplicated = definitions.clone();
+    duplicated.push_back(definitions.get(1).unwrap());
+    assert_eq!(
+        client.try_set_attribute_schema(&extended, &creator, &duplicated),
+        Err(Ok(Error::InvalidConfig))
+    );
+    client.set_attribute_schema(&extended, &editor, &definitions);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::AttributeSchemaUpdated {
+            collection_id: extended,
+            version: EVENT_VERSION,
+            attribute_count: 4,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.attribute_schema(&extended), definitions);
+}
+
+#[test]
+fn test_basic_and_extended_metadata_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let attributes = |pairs: &[(&str, &str)]| {
+        let mut map = Map::new(&env);
+        for (key, value) in pairs {
+            map.set(String::from_str(&env, key), String::from_str(&env, value));
+        }
+        map
+    };
+    let definitions = vec![
+        &env,
+        AttributeDefinition {
+            key: String::from_str(&env, "rarity"),
+            value_type: AttributeType::Enum(vec![
+                &env,
+                String::from_str(&env, "common"),
+                String::from_str(&env, "rare"),
+            ]),
+            required: true,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "level"),
+            value_type: AttributeType::Integer,
+            required: false,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "weight"),
+            value_type: AttributeType::Decimal,
+            required: false,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "born"),
+            value_type: AttributeType::Timestamp,
+            required: false,
+        },
+    ];
+
+    let extended = create_collection_with_schema(&env, &client, &creator, 1);
+    client.set_attribute_schema(&extended, &creator, &definitions);
+
+    // Basic collections take a uri only
+    let basic = create_collection_with_schema(&env, &client, &creator, 0);
+    client.mint(&basic, &holder, &uri, &None);
+    assert_eq!(
+        client.try_mint(
+            &basic,
+            &holder,
+            &uri,
+            &Some(attributes(&[("rarity", "rare")]))
+        ),
+        Err(Ok(Error::InvalidMetadata))
+    );
+
+    // Extended checks declared keys, required keys and value length, not types
+    client.mint(
+        &extended,
+        &holder,
+        &uri,
+        &Some(attributes(&[("rarity", "mythic"), ("level", "high")])),
+    );
+    assert_eq!(
+        client.try_mint(
+            &extended,
+            &holder,
+            &uri,
+            &Some(attributes(&[("rarity", "rare"), ("colour", "red")]))
+        ),
+        Err(Ok(Error::InvalidMetadata))
+    );
+    assert_eq!(
+        client.try_mint(
+            &extended,
+            &holder,
+            &uri,
+            &Some(attributes(&[("level", "3")]))
+        ),
+        Err(Ok(Error::InvalidMetadata))
+    );
+    let long_value = "x".repeat(257);
+    assert_eq!(
+        client.try_mint(
+            &extended,
+            &holder,
+            &uri,
+            &Some(attributes(&[("rarity", &long_value)]))
+        ),
+        Err(Ok(Error::InvalidMetadata))
+    );
+}
+
+#[test]
+fn test_advanced_metadata_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let attributes = |pairs: &[(&str, &str)]| {
+        let mut map = Map::new(&env);
+        for (key, value) in pairs {
+            map.set(String::from_str(&env, key), String::from_str(&env, value));
+        }
+        map
+    };
+    let definitions = vec![
+        &env,
+        AttributeDefinition {
+            key: String::from_str(&env, "rarity"),
+            value_type: AttributeType::Enum(vec![
+                &env,
+                String::from_str(&env, "common"),
+                String::from_str(&env, "rare"),
+            ]),
+            required: true,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "level"),
+            value_type: AttributeType::Integer,
+            required: false,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "weight"),
+            value_type: AttributeType::Decimal,
+            required: false,
+        },
+        AttributeDefinition {
+            key: String::from_str(&env, "born"),
+            value_type: AttributeType::Timestamp,
+            required: false,
+        },
+    ];
+
+    // Advanced also parses every value as its declared type
+    let advanced = create_collection_with_schema(&env, &client, &creator, 2);
+    client.set_attribute_schema(&advanced, &creator, &definitions);
+    let token_id = client.mint(
+        &advanced,
+        &holder,
+        &uri,
+        &Some(attributes(&[
+            ("rarity", "rare"),
+            ("level", "-3"),
+            ("weight", "12.50"),
+            ("born", "1700000000"),
+        ])),
+    );
+    for invalid in [
+        [("rarity", "mythic"), ("level", "3")],
+        [("rarity", "rare"), ("level", "3.5")],
+        [("rarity", "rare"), ("weight", "12.")],
+        [("rarity", "rare"), ("born", "-1")],
+    ] {
+        assert_eq!(
+            client.try_mint(&advanced, &holder, &uri, &Some(attributes(&invalid))),
+            Err(Ok(Error::InvalidMetadata))
+        );
+    }
+    assert_eq!(
+        client.try_batch_mint(
+            &advanced,
+            &holder,
+            &vec![&env, uri.clone(), uri.clone()],
+            &Some(vec![
+                &env,
+                attributes(&[("rarity", "common")]),
+                attributes(&[("weight", "heavy")]),
+            ]),
+        ),
+        Err(Ok(Error::InvalidMetadata))
+    );
+
+    // Metadata updates are held to the same schema
+    assert_eq!(
+        client.try_update_token_metadata(
+            &advanced,
+            &creator,
+            &token_id,
+            &uri,
+            &attributes(&[("rarity", "rare"), ("born", "yesterday")]),
+        ),
+        Err(Ok(Error::InvalidMetadata))
+    );
+    client.update_token_metadata(
+        &advanced,
+        &creator,
+        &token_id,
+        &uri,
+        &attributes(&[("rarity", "common"), ("born", "1700000001")]),
+    );
+}
+
+#[test]
+fn test_delayed_reveal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let editor = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let suffix = String::from_str(&env, ".json");
+    let placeholder = String::from_str(&env, "ipfs://placeholder.json");
+    let provenance = BytesN::from_array(&env, &[7; 32]);
+
+    // Without a URI config tokens keep the uri supplied at mint
+    let collection_id = create_test_collection(&env, &client, &creator);
+    assert_eq!(
+        client.try_reveal(&collection_id, &creator, &uri),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_token_uri_config(
+            &collection_id,
+            &holder,
+            &suffix,
+            &Some(placeholder.clone()),
+            &Some(provenance.clone())
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.grant_role(&collection_id, &creator, &Role::MetadataEditor, &editor);
+    client.set_token_uri_config(
+        &collection_id,
+        &editor,
+        &suffix,
+        &Some(placeholder.clone()),
+        &Some(provenance.clone()),
+    );
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::TokenUriConfigured {
+            collection_id,
+            version: EVENT_VERSION,
+            provenance_hash: Some(provenance.clone()),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+
+    let first = client.mint(&collection_id, &holder, &uri, &None);
+    let mut token_id = first;
+    for _ in 0..10 {
+        token_id = client.mint(&collection_id, &holder, &uri, &None);
+    }
+    assert_eq!(client.token_uri(&collection_id, &first), placeholder);
+    assert_eq!(client.token_uri(&collection_id, &token_id), placeholder);
+
+    // The provenance commitment cannot move once minting has started
+    assert_eq!(
+        client.try_set_token_uri_config(&collection_id, &creator, &suffix, &None, &None),
+        Err(Ok(Error::MintStarted))
+    );
+
+    let revealed = String::from_str(&env, "ipfs://revealed/");
+    assert_eq!(
+        client.try_reveal(&collection_id, &holder, &revealed),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.reveal(&collection_id, &editor, &revealed);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::Revealed {
+            collection_id,
+            version: EVENT_VERSION,
+            base_uri: revealed.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(
+        client.token_uri(&collection_id, &first),
+        String::from_str(&env, "ipfs://revealed/1.json")
+    );
+    assert_eq!(
+        client.token_uri(&collection_id, &token_id),
+        String::from_str(&env, "ipfs://revealed/11.json")
+    );
+
+    let config = client.token_uri_config(&collection_id).unwrap();
+    assert!(config.revealed);
+    assert_eq!(config.provenance_hash, Some(provenance));
+    assert_eq!(
+        client.try_reveal(&collection_id, &creator, &revealed),
+        Err(Ok(Error::AlreadyRevealed))
+    );
+}
+
+#[test]
+fn test_token_uri_config_after_reveal_or_freeze() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let suffix = String::from_str(&env, ".json");
+    let placeholder = String::from_str(&env, "ipfs://placeholder.json");
+
+    // A reveal cannot be undone by configuring the collection again
+    client.set_token_uri_config(
+        &collection_id,
+        &creator,
+        &suffix,
+        &Some(placeholder.clone()),
+        &None,
+    );
+    client.reveal(
+        &collection_id,
+        &creator,
+        &String::from_str(&env, "ipfs://revealed/"),
+    );
+    assert_eq!(
+        client.try_set_token_uri_config(
+            &collection_id,
+            &creator,
+            &suffix,
+            &Some(placeholder.clone()),
+            &None
+        ),
+        Err(Ok(Error::AlreadyRevealed))
+    );
+    assert!(client.token_uri_config(&collection_id).unwrap().revealed);
+
+    // Nor can frozen metadata be pointed at a new placeholder
+    let frozen = create_test_collection(&env, &client, &creator);
+    client.freeze_metadata(&frozen, &creator, &None);
+    assert_eq!(
+        client.try_set_token_uri_config(&frozen, &creator, &suffix, &Some(placeholder), &None),
+        Err(Ok(Error::MetadataFrozen))
+    );
+    assert_eq!(client.token_uri_config(&frozen), None);
+}
+
+#[test]
+fn test_derived_token_uris() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    let revealed = String::from_str(&env, "ipfs://revealed/");
+
+    // Without a placeholder URIs are derived from the start
+    let derived = create_test_collection(&env, &client, &creator);
+    client.set_token_uri_config(
+        &derived,
+        &creator,
+        &String::from_str(&env, ""),
+        &None,
+        &None,
+    );
+    let token_id = client.mint(&derived, &holder, &uri, &None);
+    assert_eq!(
+        client.token_uri(&derived, &token_id),
+        String::from_str(&env, "https://api.nftopia.com/metadata/1")
+    );
+    assert_eq!(
+        client.try_reveal(&derived, &creator, &revealed),
+        Err(Ok(Error::InvalidConfig))
+    );
+}
+
+#[test]
+fn test_royalty_info_for_sale() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    // create_test_collection pays 5% to the creator
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let first = client.mint(&collection_id, &holder, &uri, &None);
+
+    assert_eq!(
+        client.royalty_info_for_sale(&collection_id, &first, &10_000),
+        (creator.clone(), 500)
+    );
+    // Fractions of the smallest unit round down
+    assert_eq!(
+        client.royalty_info_for_sale(&collection_id, &first, &39),
+        (creator.clone(), 1)
+    );
+    assert_eq!(
+        client.try_royalty_info_for_sale(&collection_id, &first, &-1),
+        Err(Ok(Error::InvalidInput))
+    );
+    assert_eq!(
+        client.try_royalty_info_for_sale(&collection_id, &99, &10_000),
+        Err(Ok(Error::TokenNotFound))
+    );
+}
+
+#[test]
+fn test_token_royalty_overrides() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let royalty_admin = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    // create_test_collection pays 5% to the creator
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let first = client.mint(&collection_id, &holder, &uri, &None);
+    let second = client.mint(&collection_id, &holder, &uri, &None);
+
+    let royalty = RoyaltyInfo {
+        recipient: artist.clone(),
+        percentage: 1_250,
+    };
+    assert_eq!(
+        client.try_set_token_royalty_info(
+            &collection_id,
+            &royalty_admin,
+            &first,
+            &Some(royalty.clone())
+        ),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.grant_role(
+        &collection_id,
+        &creator,
+        &Role::RoyaltyAdmin,
+        &royalty_admin,
+    );
+    assert_eq!(
+        client.try_set_token_royalty_info(
+            &collection_id,
+            &royalty_admin,
+            &first,
+            &Some(RoyaltyInfo {
+                recipient: artist.clone(),
+                percentage: 2_501,
+            })
+        ),
+        Err(Ok(Error::InvalidRoyaltyPercentage))
+    );
+    assert_eq!(
+        client.try_set_token_royalty_info(
+            &collection_id,
+            &royalty_admin,
+            &99,
+            &Some(royalty.clone())
+        ),
+        Err(Ok(Error::TokenNotFound))
+    );
+
+    client.set_token_royalty_info(
+        &collection_id,
+        &royalty_admin,
+        &first,
+        &Some(royalty.clone()),
+    );
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::TokenRoyaltyUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            token_id: first,
+            royalty: Some(royalty.clone()),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(
+        client.token_royalty_info(&collection_id, &first),
+        Some(royalty)
+    );
+
+    // The override wins over the collection default, which still applies elsewhere
+    client.set_royalty_info(&collection_id, &royalty_admin, &creator, &200);
+    assert_eq!(
+        client.royalty_info_for_sale(&collection_id, &first, &10_000),
+        (artist.clone(), 1_250)
+    );
+    assert_eq!(
+        client.royalty_info_for_sale(&collection_id, &second, &10_000),
+        (creator.clone(), 200)
+    );
+
+    client.set_token_royalty_info(&collection_id, &royalty_admin, &first, &None);
+    assert_eq!(client.token_royalty_info(&collection_id, &first), None);
+    assert_eq!(
+        client.royalty_info_for_sale(&collection_id, &first, &10_000),
+        (creator, 200)
+    );
+}
+
+#[test]
+fn test_royalty_splits_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let share = |recipient: &Address, share: u32| RoyaltyShare {
+        recipient: recipient.clone(),
+        share,
+    };
+
+    let client = setup_factory(&env, &owner);
+
+    // create_test_collection pays 5% to the creator
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let token_id = client.mint(&collection_id, &holder, &uri, &None);
+    assert_eq!(
+        client.royalty_payouts_for_sale(&collection_id, &token_id, &10_000),
+        vec![&env, (creator.clone(), 500)]
+    );
+
+    assert_eq!(
+        client.try_set_royalty_splits(
+            &collection_id,
+            &creator,
+            &vec![&env, share(&creator, 200), share(&artist, 200)]
+        ),
+        Err(Ok(Error::RoyaltySplitMismatch))
+    );
+    assert_eq!(
+        client.try_set_royalty_splits(
+            &collection_id,
+            &creator,
+            &vec![&env, share(&creator, 250), share(&creator, 250)]
+        ),
+        Err(Ok(Error::InvalidRoyaltySplit))
+    );
+    assert_eq!(
+        client.try_set_royalty_splits(
+            &collection_id,
+            &creator,
+            &vec![&env, share(&creator, 500), share(&artist, 0)]
+        ),
+        Err(Ok(Error::InvalidRoyaltySplit))
+    );
+    let mut crowded = vec![&env];
+    for _ in 0..11 {
+        crowded.push_back(share(&Address::generate(&env), 1));
+    }
+    assert_eq!(
+        client.try_set_royalty_splits(&collection_id, &creator, &crowded),
+        Err(Ok(Error::InvalidRoyaltySplit))
+    );
+}
+
+#[test]
+fn test_royalty_splits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let developer = Address::generate(&env);
+    let share = |recipient: &Address, share: u32| RoyaltyShare {
+        recipient: recipient.clone(),
+        share,
+    };
+
+    let client = setup_factory(&env, &owner);
+
+    // create_test_collection pays 5% to the creator
+    let collection_id = create_test_collection(&env, &client, &creator);
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let token_id = client.mint(&collection_id, &holder, &uri, &None);
+    assert_eq!(
+        client.royalty_payouts_for_sale(&collection_id, &token_id, &10_000),
+        vec![&env, (creator.clone(), 500)]
+    );
+
+    let shares = vec![
+        &env,
+        share(&creator, 250),
+        share(&artist, 150),
+        share(&developer, 100),
+    ];
+    client.set_royalty_splits(&collection_id, &creator, &shares);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::RoyaltySplitsUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            recipient_count: 3,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(client.royalty_splits(&collection_id), shares);
+
+    assert_eq!(
+        client.royalty_payouts_for_sale(&collection_id, &token_id, &10_000),
+        vec![
+            &env,
+            (creator.clone(), 250),
+            (artist.clone(), 150),
+            (developer.clone(), 100)
+        ]
+    );
+    // A single recipient would hide the split, so only payouts are reported
+    assert_eq!(
+        client.try_royalty_info_for_sale(&collection_id, &token_id, &10_000),
+        Err(Ok(Error::RoyaltySplitsActive))
+    );
+
+    // Rounding dust goes to the first recipient so payouts add up to the
+    // 4 owed on the sale
+    assert_eq!(
+        client.royalty_payouts_for_sale(&collection_id, &token_id, &99),
+        vec![
+            &env,
+            (creator.clone(), 3),
+            (artist.clone(), 1),
+            (developer.clone(), 0)
+        ]
+    );
+
+    // The total cannot drift away from the split table
+    assert_eq!(
+        client.try_set_royalty_info(&collection_id, &creator, &creator, &600),
+        Err(Ok(Error::RoyaltySplitMismatch))
+    );
+
+    client.set_royalty_splits(&collection_id, &creator, &vec![&env]);
+    assert_eq!(client.royalty_splits(&collection_id), vec![&env]);
+    client.set_royalty_info(&collection_id, &creator, &artist, &600);
+    assert_eq!(
+        client.royalty_payouts_for_sale(&collection_id, &token_id, &10_000),
+        vec![&env, (artist, 600)]
+    );
+}
+
+#[test]
+fn test_transfer_policies() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let recovery = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+
+    let create = |transfer_policy: u32| {
+        client.try_create_collection(
+            &creator,
+            &String::from_str(&env, "Membership"),
+            &String::from_str(&env, "MEMBER"),
+            &String::from_str(&env, "Membership passes"),
+            &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+            &None::<u32>,
+            &true,
+            &0,
+            &creator,
+            &0,
+            &true,
+            &false,
+            &transfer_policy,
+        )
+    };
+    assert_eq!(create(3), Err(Ok(Error::InvalidConfig)));
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+
+    // Soulbound tokens can be minted and burned but never moved or approved
+    let soulbound = create(1).unwrap().unwrap();
+    assert_eq!(
+        client
+            .get_collection_info(&soulbound)
+            .config
+            .transfer_policy,
+        TransferPolicy::Soulbound
+    );
+    let token_id = client.mint(&soulbound, &holder, &uri, &None);
+    assert_eq!(
+        client.try_transfer(&soulbound, &holder, &recovery, &token_id),
+        Err(Ok(Error::TransferRestricted))
+    );
+    assert_eq!(
+        client.try_transfer_from(&soulbound, &creator, &holder, &recovery, &token_id),
+        Err(Ok(Error::TransferRestricted))
+    );
+    assert_eq!(
+        client.try_batch_transfer(&soulbound, &holder, &recovery, &vec![&env, token_id]),
+        Err(Ok(Error::TransferRestricted))
+    );
+    assert_eq!(
+        client.try_approve(&soulbound, &holder, &operator, &token_id, &100),
+        Err(Ok(Error::TransferRestricted))
+    );
+    assert_eq!(
+        client.try_set_approval_for_all(&soulbound, &holder, &operator, &100),
+        Err(Ok(Error::TransferRestricted))
+    );
+    client.burn(&soulbound, &holder, &token_id);
+    assert_eq!(client.balance_of(&soulbound, &holder), 0);
+
+    // Only the collection admin can reassign admin-transfer-only tokens
+    let recoverable = create(2).unwrap().unwrap();
+    let token_id = client.mint(&recoverable, &holder, &uri, &None);
+    assert_eq!(
+        client.try_transfer(&recoverable, &holder, &operator, &token_id),
+        Err(Ok(Error::TransferRestricted))
+    );
+    assert_eq!(
+        client.try_approve(&recoverable, &holder, &operator, &token_id, &100),
+        Err(Ok(Error::TransferRestricted))
+    );
+    client.transfer_from(&recoverable, &creator, &holder, &recovery, &token_id);
+    assert_eq!(client.owner_of(&recoverable, &token_id), recovery);
+    assert_eq!(
+        client.try_transfer_from(&recoverable, &creator, &holder, &recovery, &token_id),
+        Err(Ok(Error::NotTokenOwner))
+    );
+
+    // Transferable collections keep the usual owner and approval rules
+    let transferable = create(0).unwrap().unwrap();
+    let token_id = client.mint(&transferable, &holder, &uri, &None);
+    assert_eq!(
+        client.try_transfer_from(&transferable, &creator, &holder, &recovery, &token_id),
+        Err(Ok(Error::NotApproved))
+    );
+    client.approve(&transferable, &holder, &operator, &token_id, &100);
+    client.transfer_from(&transferable, &operator, &holder, &recovery, &token_id);
+    assert_eq!(client.owner_of(&transferable, &token_id), recovery);
+}
+
+#[test]
+fn test_collection_upgrades() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+    let wasm_hash = upload_collection_wasm(&env);
+
+    // create_test_collection is not upgradeable
+    let fixed = create_test_collection(&env, &client, &creator);
+    assert_eq!(
+        client.collection_contract_wasm(&fixed),
+        Some(wasm_hash.clone())
+    );
+    assert_eq!(
+        client.try_upgrade_collection(&fixed, &creator, &wasm_hash),
+        Err(Ok(Error::NotUpgradeable))
+    );
+
+    let collection_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Upgradeable"),
+        &String::from_str(&env, "UPG"),
+        &String::from_str(&env, "An upgradeable collection"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &true,
+        &0,
+    );
+    assert_eq!(
+        client.try_upgrade_collection(&collection_id, &stranger, &wasm_hash),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // The admin upgrades through the deployed collection contract
+    let collection =
+        collection_contract::Client::new(&env, &client.get_collection_address(&collection_id));
+    collection.upgrade(&creator, &wasm_hash);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::CollectionUpgraded {
+            collection_id,
+            version: EVENT_VERSION,
+            old_wasm_hash: Some(wasm_hash.clone()),
+            new_wasm_hash: wasm_hash.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(collection.collection_id(), collection_id);
+
+    // Renouncing is permanent
+    collection.renounce_upgradeability(&creator);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::UpgradeabilityRenounced {
+            collection_id,
+            version: EVENT_VERSION,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert!(
+        !client
+            .get_collection_info(&collection_id)
+            .config
+            .is_upgradeable
+    );
+    assert_eq!(
+        client.try_upgrade_collection(&collection_id, &creator, &wasm_hash),
+        Err(Ok(Error::NotUpgradeable))
+    );
+    assert_eq!(
+        client.try_renounce_upgradeability(&collection_id, &creator),
+        Err(Ok(Error::NotUpgradeable))
+    );
+}
+
+#[test]
+fn test_legacy_collection_is_not_upgradeable() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+    let wasm_hash = upload_collection_wasm(&env);
+    let collection_id = client.create_collection(
+        &creator,
+        &String::from_str(&env, "Upgradeable"),
+        &String::from_str(&env, "UPG"),
+        &String::from_str(&env, "An upgradeable collection"),
+        &String::from_str(&env, "https://api.nftopia.com/metadata/"),
+        &None::<u32>,
+        &true,
+        &500,
+        &creator,
+        &0,
+        &true,
+        &true,
+        &0,
+    );
+
+    // Collections migrated from v1 live in the factory itself, so there is no
+    // collection contract to upgrade even when the config allows it
+    env.as_contract(&client.address, || {
+        let mut info = <DataKey as Storage>::get_collection_info(&env, collection_id).unwrap();
+        info.address = client.address.clone();
+        <DataKey as Storage>::set_collection_info(&env, collection_id, &info);
+    });
+    assert_eq!(
+        client.try_upgrade_collection(&collection_id, &creator, &wasm_hash),
+        Err(Ok(Error::NotUpgradeable))
+    );
+}
+
+#[test]
+fn test_factory_upgrade() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let client = setup_factory(&env, &owner);
+    let wasm_hash = upload_collection_wasm(&env);
+
+    // Only the owner can replace the factory itself
+    assert_eq!(
+        client.try_upgrade(&creator, &wasm_hash),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.upgrade(&owner, &wasm_hash);
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::FactoryUpgraded {
+            version: EVENT_VERSION,
+            old_wasm_hash: None,
+            new_wasm_hash: wasm_hash,
+        }
+        .to_xdr(&env, &client.address)]
+    );
+}
+
+#[test]
+fn test_migrate_v1_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let contract_id = env.register(CollectionFactoryContract, ());
+    let client = CollectionFactoryContractClient::new(&env, &contract_id);
+
+    // Seed a factory as v1 left it, everything in instance storage: two
+    // collections, the first holding tokens 1..=5 with token 4 burned by
+    // carol, and no version marker or indexes
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    let v1_metadata = |token_id: u32| TokenMetadata {
+        token_id,
+        uri: uri.clone(),
+        attributes: Map::new(&env),
+        creator: creator.clone(),
+        created_at: 1_000,
+        updated_at: None,
+    };
+    let royalty = RoyaltyInfo {
+        recipient: creator.clone(),
+        percentage: 500,
+    };
+    env.as_contract(&contract_id, || {
+        let storage = env.storage().instance();
+        storage.set(
+            &DataKeyV1::FactoryConfig,
+            &FactoryConfigV1 {
+                owner: owner.clone(),
+                factory_fee: 0,
+                max_collections: None,
+                total_collections: 2,
+                accumulated_fees: 0,
+                is_active: true,
+            },
+        );
+
+        for collection_id in 1..=2u64 {
+            storage.set(
+                &DataKeyV1::CollectionInfo(collection_id),
+                &CollectionInfoV1 {
+                    address: contract_id.clone(),
+                    creator: creator.clone(),
+                    config: CollectionConfigV1 {
+                        name: String::from_str(&env, "Legacy"),
+                        symbol: String::from_str(&env, "OLD"),
+                        description: String::from_str(&env, "A v1 collection"),
+                        base_uri: String::from_str(&env, "https://api.nftopia.com/metadata/"),
+                        max_supply: None,
+                        is_public_mint: true,
+                        royalty_percentage: 500,
+                        royalty_recipient: creator.clone(),
+                        metadata_schema: MetadataSchema::Basic,
+                        is_pausable: true,
+                        is_upgradeable: false,
+                    },
+                    created_at: 1_000,
+                    total_tokens: if collection_id == 1 { 4 } else { 0 },
+                    is_paused: false,
+                },
+            );
+        }
+
+        for (token_id, holder) in [(1, &alice), (2, &bob), (3, &alice), (5, &alice)] {
+            storage.set(&DataKeyV1::TokenOwner(1, token_id), holder);
+        }
+        for token_id in 1..=5 {
+            storage.set(
+                &DataKeyV1::TokenMetadata(1, token_id),
+                &v1_metadata(token_id),
+            );
+        }
+        storage.set(&DataKeyV1::NextTokenId(1), &6u32);
+        storage.set(&DataKeyV1::Balance(1, alice.clone()), &3u32);
+        storage.set(&DataKeyV1::Balance(1, bob.clone()), &1u32);
+        storage.set(&DataKeyV1::Balance(1, carol.clone()), &0u32);
+        storage.set(&DataKeyV1::Approved(1, 2), &operator);
+        storage.set(
+            &DataKeyV1::ApprovedForAll(1, alice.clone(), operator.clone()),
+            &true,
+        );
+        storage.set(&DataKeyV1::WhitelistForMint(2, carol.clone()), &true);
+        storage.set(&DataKeyV1::RoyaltyInfo(1), &royalty);
+        storage.set(&DataKeyV1::IsPaused(2), &true);
+    });
+
+    // Versioned structs are unreadable until the migration has run
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(
+        client.try_get_factory_config(),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_get_collection_info(&1),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_owner_of(&1, &1),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_token_metadata(&1, &1),
+        Err(Ok(Error::MigrationRequired))
+    );
+
+    // Balances and approvals would read as empty, so they fail the same way
+    assert_eq!(
+        client.try_balance_of(&1, &alice),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_get_approved(&1, &2),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_is_approved_for_all(&1, &alice, &operator),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_tokens_of_owner(&1, &alice, &0, &10),
+        Err(Ok(Error::MigrationRequired))
+    );
+    assert_eq!(
+        client.try_initialize(&stranger),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+    assert_eq!(
+        client.try_migrate(&stranger, &3),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.try_migrate(&owner, &0), Err(Ok(Error::InvalidInput)));
+
+    // Small batches resume where the previous call stopped
+    let mut calls = 1;
+    while !client.migrate(&owner, &3) {
+        calls += 1;
+        assert_eq!(client.schema_version(), 1);
+    }
+    assert!(calls > 1);
+    assert_eq!(
+        env.events().all().filter_by_contract(&contract_id),
+        [events::SchemaMigrated {
+            version: EVENT_VERSION,
+            from_schema: 1,
+            to_schema: 2,
+        }
+        .to_xdr(&env, &contract_id)]
+    );
+    assert_eq!(client.schema_version(), 2);
+    assert!(client.migrate(&owner, &3));
+
+    let config = client.get_factory_config();
+    assert_eq!(config.owner, owner);
+    assert_eq!(config.total_collections, 2);
+    assert_eq!(config.fee_token, None);
+    assert_eq!(config.platform_fee_bps, 0);
+
+    let info = client.get_collection_info(&1);
+    assert_eq!(info.admin, creator);
+    assert_eq!(info.total_tokens, 4);
+    assert_eq!(info.config.transfer_policy, TransferPolicy::Transferable);
+    assert_eq!(
+        client.collections_by_creator(&creator, &0, &10, &None),
+        vec![&env, 1, 2]
+    );
+    assert_eq!(client.royalty_info(&1), Some(royalty));
+
+    // Tokens keep their metadata and approvals and gain enumeration indexes
+    assert_eq!(client.token_metadata(&1, &3), v1_metadata(3));
+    assert_eq!(client.tokens(&1, &0, &10), vec![&env, 1, 2, 3, 5]);
+    assert_eq!(
+        client.tokens_of_owner(&1, &alice, &0, &10),
+        vec![&env, 1, 3, 5]
+    );
+    assert_eq!(client.tokens_of_owner(&1, &bob, &0, &10), vec![&env, 2]);
+    assert_eq!(client.balance_of(&1, &alice), 3);
+    assert_eq!(client.get_approved(&1, &2), Some(operator.clone()));
+
+    // Address-keyed entries wait for migrate_entries
+    assert!(!client.is_approved_for_all(&1, &alice, &operator));
+    let legacy_keys = vec![
+        &env,
+        LegacyKey::ApprovedForAll(1, alice.clone(), operator.clone()),
+        LegacyKey::WhitelistForMint(2, carol.clone()),
+        LegacyKey::Balance(1, carol.clone()),
+        LegacyKey::Balance(2, stranger.clone()),
+    ];
+    assert_eq!(
+        client.try_migrate_entries(&stranger, &legacy_keys),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(client.migrate_entries(&owner, &legacy_keys), 3);
+    assert_eq!(client.migrate_entries(&owner, &legacy_keys), 0);
+    assert!(client.is_approved_for_all(&1, &alice, &operator));
+
+    env.as_contract(&contract_id, || {
+        assert!(<DataKey as Storage>::is_whitelisted_for_mint(
+            &env, 2, &carol
+        ));
+        assert!(<DataKey as Storage>::is_collection_paused(&env, 2));
+
+        // Nothing is left behind in instance storage but the v2 entries
+        let storage = env.storage().instance();
+        assert!(!storage.has(&DataKeyV1::CollectionInfo(1)));
+        assert!(!storage.has(&DataKeyV1::NextTokenId(1)));
+        assert!(!storage.has(&DataKeyV1::TokenOwner(1, 1)));
+        assert!(!storage.has(&DataKeyV1::TokenMetadata(1, 4)));
+        assert!(!storage.has(&DataKeyV1::Balance(1, alice.clone())));
+        assert!(!storage.has(&DataKeyV1::Balance(1, carol.clone())));
+        assert!(!storage.has(&DataKeyV1::Approved(1, 2)));
+        assert!(!storage.has(&DataKeyV1::ApprovedForAll(
+            1,
+            alice.clone(),
+            operator.clone()
+        )));
+        assert!(!storage.has(&DataKeyV1::WhitelistForMint(2, carol.clone())));
+        assert!(!storage.has(&DataKeyV1::RoyaltyInfo(1)));
+        assert!(!storage.has(&DataKeyV1::IsPaused(2)));
+    });
+
+    client.transfer(&1, &alice, &bob, &3);
+    assert_eq!(
+        client.tokens_of_owner(&1, &alice, &0, &10),
+        vec![&env, 1, 5]
+    );
+    assert_eq!(client.tokens_of_owner(&1, &bob, &0, &10), vec![&env, 2, 3]);
+    assert_eq!(client.mint(&1, &bob, &uri, &None), 6);
+    assert_eq!(client.tokens(&1, &0, &10), vec![&env, 1, 2, 3, 5, 6]);
+}
+
+#[test]
+fn test_migrate_v1_storage_with_fee_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let fee_token = Address::generate(&env);
+
+    let contract_id = env.register(CollectionFactoryContract, ());
+    let client = CollectionFactoryContractClient::new(&env, &contract_id);
+
+    // Unversioned factories that charged fees in a token stored it in the
+    // otherwise v1-shaped config
+    env.as_contract(&contract_id, || {
+        env.storage().instance().set(
+            &DataKeyV1::FactoryConfig,
+            &FactoryConfigV1WithFeeToken {
+                owner: owner.clone(),
+                factory_fee: 100,
+                fee_token: Some(fee_token.clone()),
+                max_collections: Some(10),
+                total_collections: 0,
+                accumulated_fees: 300,
+                is_active: true,
+            },
+        );
+    });
+
+    assert_eq!(client.schema_version(), 1);
+    assert!(client.migrate(&owner, &10));
+
+    let config = client.get_factory_config();
+    assert_eq!(config.owner, owner);
+    assert_eq!(config.factory_fee, 100);
+    assert_eq!(config.fee_token, Some(fee_token));
+    assert_eq!(config.max_collections, Some(10));
+    assert_eq!(config.accumulated_fees, 300);
+}
+
+#[test]
+fn test_update_collection_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let no_change = CollectionConfigUpdate {
+        description: None,
+        base_uri: None,
+        max_supply: None,
+        is_public_mint: None,
+        is_pausable: None,
+    };
+
+    assert_eq!(
+        client.try_update_collection_config(&collection_id, &holder, &no_change),
+        Err(Ok(Error::Unauthorized))
+    );
+
+    // Cosmetic fields change freely and the event records both versions
+    let old_config = client.get_collection_info(&collection_id).config;
+    client.update_collection_config(
+        &collection_id,
+        &creator,
+        &CollectionConfigUpdate {
+            description: Some(String::from_str(&env, "Season two")),
+            base_uri: Some(String::from_str(&env, "ipfs://season-two/")),
+            max_supply: Some(10),
+            is_public_mint: Some(false),
+            is_pausable: None,
+        },
+    );
+    let mut new_config = old_config.clone();
+    new_config.description = String::from_str(&env, "Season two");
+    new_config.base_uri = String::from_str(&env, "ipfs://season-two/");
+    new_config.max_supply = Some(10);
+    new_config.is_public_mint = false;
+    assert_eq!(
+        env.events().all().filter_by_contract(&client.address),
+        [events::CollectionConfigUpdated {
+            collection_id,
+            version: EVENT_VERSION,
+            old_config,
+            new_config: new_config.clone(),
+        }
+        .to_xdr(&env, &client.address)]
+    );
+    assert_eq!(
+        client.get_collection_info(&collection_id).config,
+        new_config
+    );
+
+    // A frozen collection keeps its base URI
+    client.freeze_metadata(&collection_id, &creator, &None);
+    assert_eq!(
+        client.try_update_collection_config(
+            &collection_id,
+            &creator,
+            &CollectionConfigUpdate {
+                base_uri: Some(String::from_str(&env, "ipfs://elsewhere/")),
+                ..no_change.clone()
+            }
+        ),
+        Err(Ok(Error::MetadataFrozen))
+    );
+}
+
+#[test]
+fn test_update_collection_max_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let no_change = CollectionConfigUpdate {
+        description: None,
+        base_uri: None,
+        max_supply: None,
+        is_public_mint: None,
+        is_pausable: None,
+    };
+    let with_supply = |max_supply: u32| CollectionConfigUpdate {
+        max_supply: Some(max_supply),
+        ..no_change.clone()
+    };
+
+    client.update_collection_config(&collection_id, &creator, &with_supply(10));
+
+    // Supply only shrinks, and never below the tokens in circulation
+    let uri = String::from_str(&env, "https://api.nftopia.com/metadata/token");
+    for _ in 0..3 {
+        client.minter_mint(&collection_id, &creator, &holder, &uri, &None);
+    }
+    assert_eq!(
+        client.try_update_collection_config(&collection_id, &creator, &with_supply(11)),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_update_collection_config(&collection_id, &creator, &with_supply(2)),
+        Err(Ok(Error::InvalidConfig))
+    );
+    client.update_collection_config(&collection_id, &creator, &with_supply(3));
+    assert_eq!(
+        client.try_minter_mint(&collection_id, &creator, &holder, &uri, &None),
+        Err(Ok(Error::MaxSupplyExceeded))
+    );
+}
+
+#[test]
+fn test_update_collection_pausability() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    let (client, collection_id) = setup_collection(&env, &owner, &creator);
+    let no_change = CollectionConfigUpdate {
+        description: None,
+        base_uri: None,
+        max_supply: None,
+        is_public_mint: None,
+        is_pausable: None,
+    };
+    let with_pausable = |is_pausable: bool| CollectionConfigUpdate {
+        is_pausable: Some(is_pausable),
+        ..no_change.clone()
+    };
+
+    // Pausability can be given up, but not while paused and not taken back
+    client.set_paused(&collection_id, &creator, &true);
+    assert_eq!(
+        client.try_update_collection_config(&collection_id, &creator, &with_pausable(false)),
+        Err(Ok(Error::InvalidConfig))
+    );
+    client.set_paused(&collection_id, &creator, &false);
+    client.update_collection_config(&collection_id, &creator, &with_pausable(false));
+    assert_eq!(
+        client.try_update_collection_config(&collection_id, &creator, &with_pausable(true)),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(
+        client.try_set_paused(&collection_id, &creator, &true),
+        Err(Ok(Error::Unauthorized))
+    );
+}